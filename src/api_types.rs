@@ -1,12 +1,16 @@
 use crate::custom_deserializers;
+use crate::errors::{APIError, APIErrorKind};
 use crate::fake_hash_map;
 use crate::ftl_types::*;
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_tuple::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::net::IpAddr;
+use std::str::FromStr;
 use std::time::Duration;
+use strum::{EnumIter, IntoEnumIterator};
 
 /// Summary Raw Struct
 #[derive(Deserialize, Serialize, Debug)]
@@ -168,6 +172,36 @@ pub struct ForwardDestinations {
     pub forward_destinations: HashMap<String, f64>,
 }
 
+/// Forward Destination Names Struct
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ForwardDestinationNames {
+    /// Forward destinations as "human_readable_name|IP" and the percentage of requests answered,
+    /// in the order used by [`OverTimeDataForwards`].
+    #[serde(deserialize_with = "custom_deserializers::deserialize_ordered_map")]
+    pub forward_destinations: Vec<(String, f64)>,
+}
+
+/// Over Time Data Forwards Struct
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OverTimeDataForwards {
+    /// Mapping from the start of each 10 minute period to the percentage of queries answered by each forward destination.
+    /// Values are in the order of `forward_destinations`.
+    #[serde(deserialize_with = "custom_deserializers::deserialize_timestamp_map")]
+    pub over_time: BTreeMap<NaiveDateTime, Vec<f64>>,
+
+    /// Forward destinations as "human_readable_name|IP" and the percentage of requests answered
+    #[serde(deserialize_with = "custom_deserializers::deserialize_ordered_map")]
+    pub forward_destinations: Vec<(String, f64)>,
+}
+
+/// Over Time Data Query Types Struct
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OverTimeDataQueryTypes {
+    /// Mapping from the start of each 10 minute period to the percentage of A and AAAA queries
+    #[serde(deserialize_with = "custom_deserializers::deserialize_timestamp_map")]
+    pub over_time: BTreeMap<NaiveDateTime, Vec<f64>>,
+}
+
 /// Query Types Struct
 #[derive(Deserialize, Serialize, Debug)]
 pub struct QueryTypes {
@@ -224,6 +258,29 @@ pub struct Query {
     pub ede: String,
 }
 
+/// Query from the long-term database
+#[derive(Deserialize_tuple, Serialize_tuple, Debug)]
+pub struct DatabaseQuery {
+    /// Timestamp of query
+    #[serde(with = "chrono::naive::serde::ts_seconds")]
+    pub timestamp: NaiveDateTime,
+
+    /// Type of query (A, AAAA, PTR, etc.)
+    pub query_type: QueryType,
+
+    /// Requested domain name
+    pub domain: String,
+
+    /// Requesting client IP or hostname
+    pub client: String,
+
+    /// Status of the query
+    pub status: QueryStatus,
+
+    /// Upstream destination, if the query was forwarded
+    pub upstream_destination: Option<String>,
+}
+
 /// All Queries Struct
 #[derive(Deserialize, Serialize, Debug)]
 pub struct AllQueries {
@@ -355,6 +412,57 @@ pub struct ListModificationResponse {
     pub message: Option<String>,
 }
 
+/// Domain list of the Pi Hole.
+/// Displayed and parsed as the list names used by the PHP API, e.g. `white` or `black_regex`.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, EnumIter)]
+pub enum DomainList {
+    /// Domains allowed by exact match (`white`)
+    ExactAllow,
+    /// Domains blocked by exact match (`black`)
+    ExactDeny,
+    /// Regular expressions of allowed domains (`white_regex`)
+    RegexAllow,
+    /// Regular expressions of blocked domains (`black_regex`)
+    RegexDeny,
+    /// Allowed domains including their subdomains (`white_wild`)
+    WildcardAllow,
+    /// Blocked domains including their subdomains (`black_wild`)
+    WildcardDeny,
+    /// Domains marked as reviewed in the audit log (`audit`)
+    Audit,
+}
+
+impl DomainList {
+    /// Name of the list used by the PHP API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DomainList::ExactAllow => "white",
+            DomainList::ExactDeny => "black",
+            DomainList::RegexAllow => "white_regex",
+            DomainList::RegexDeny => "black_regex",
+            DomainList::WildcardAllow => "white_wild",
+            DomainList::WildcardDeny => "black_wild",
+            DomainList::Audit => "audit",
+        }
+    }
+}
+
+impl fmt::Display for DomainList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DomainList {
+    type Err = APIError;
+
+    fn from_str(list: &str) -> Result<Self, Self::Err> {
+        DomainList::iter()
+            .find(|domain_list| domain_list.as_str() == list)
+            .ok_or_else(|| APIErrorKind::InvalidList.into())
+    }
+}
+
 /// Custom List Domain Struct
 #[derive(Deserialize, Serialize, Debug)]
 pub struct CustomListDomainDetails {
@@ -375,11 +483,138 @@ pub struct CustomListDomainDetails {
     #[serde(with = "chrono::naive::serde::ts_seconds")]
     pub date_modified: NaiveDateTime,
     /// Comments
+    #[serde(
+        default,
+        deserialize_with = "custom_deserializers::deserialize_null_to_default"
+    )]
     pub comment: String,
     /// Groups
+    #[serde(default)]
     pub groups: Vec<u64>,
 }
 
+/// Group of clients, domains and adlists sharing a blocking policy
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Group {
+    /// Group ID
+    pub id: u64,
+    /// Whether the group is enabled
+    #[serde(deserialize_with = "custom_deserializers::deserialize_uint_to_bool")]
+    pub enabled: bool,
+    /// Name of the group
+    pub name: String,
+    /// Date added
+    #[serde(with = "chrono::naive::serde::ts_seconds")]
+    pub date_added: NaiveDateTime,
+    /// Date modified
+    #[serde(with = "chrono::naive::serde::ts_seconds")]
+    pub date_modified: NaiveDateTime,
+    /// Description
+    pub description: Option<String>,
+}
+
+/// Client configured in the group management
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GroupClient {
+    /// Client ID
+    pub id: u64,
+    /// IP address, subnet, MAC address, hostname or interface of the client
+    pub ip: String,
+    /// Date added
+    #[serde(with = "chrono::naive::serde::ts_seconds")]
+    pub date_added: NaiveDateTime,
+    /// Date modified
+    #[serde(with = "chrono::naive::serde::ts_seconds")]
+    pub date_modified: NaiveDateTime,
+    /// Comment
+    pub comment: Option<String>,
+    /// IDs of the groups the client is assigned to
+    #[serde(default)]
+    pub groups: Vec<u64>,
+    /// Hostname of the client, if known
+    pub name: Option<String>,
+}
+
+/// Adlist the gravity database is built from
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Adlist {
+    /// Adlist ID
+    pub id: u64,
+    /// URL of the adlist
+    pub address: String,
+    /// Whether the adlist is enabled
+    #[serde(deserialize_with = "custom_deserializers::deserialize_uint_to_bool")]
+    pub enabled: bool,
+    /// Date added
+    #[serde(with = "chrono::naive::serde::ts_seconds")]
+    pub date_added: NaiveDateTime,
+    /// Date modified
+    #[serde(with = "chrono::naive::serde::ts_seconds")]
+    pub date_modified: NaiveDateTime,
+    /// Comment
+    pub comment: Option<String>,
+    /// IDs of the groups the adlist is assigned to
+    #[serde(default)]
+    pub groups: Vec<u64>,
+    /// Date of the last gravity update which downloaded the adlist
+    #[serde(default, with = "chrono::naive::serde::ts_seconds_option")]
+    pub date_updated: Option<NaiveDateTime>,
+    /// Number of domains on the adlist
+    #[serde(default)]
+    pub number: u64,
+    /// Number of invalid domains on the adlist
+    #[serde(default)]
+    pub invalid_domains: u64,
+    /// Result of the last download
+    #[serde(default)]
+    pub status: AdlistStatus,
+}
+
+/// Result of the last download of an adlist by gravity
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(from = "u8", into = "u8")]
+pub enum AdlistStatus {
+    /// Not downloaded yet
+    #[default]
+    NotDownloaded,
+    /// Downloaded and changed
+    Updated,
+    /// Downloaded and unchanged
+    Unchanged,
+    /// Download failed, the local copy was used
+    UsedLocalCopy,
+    /// Download failed and there is no local copy
+    Unavailable,
+    /// Status not known to this version of the crate
+    Unknown(u8),
+}
+
+impl From<u8> for AdlistStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => AdlistStatus::NotDownloaded,
+            1 => AdlistStatus::Updated,
+            2 => AdlistStatus::Unchanged,
+            3 => AdlistStatus::UsedLocalCopy,
+            4 => AdlistStatus::Unavailable,
+            value => AdlistStatus::Unknown(value),
+        }
+    }
+}
+
+impl From<AdlistStatus> for u8 {
+    fn from(status: AdlistStatus) -> Self {
+        match status {
+            AdlistStatus::NotDownloaded => 0,
+            AdlistStatus::Updated => 1,
+            AdlistStatus::Unchanged => 2,
+            AdlistStatus::UsedLocalCopy => 3,
+            AdlistStatus::Unavailable => 4,
+            AdlistStatus::Unknown(value) => value,
+        }
+    }
+}
+
 /// Local/Custom List Domain Struct
 #[derive(Deserialize, Serialize, Debug)]
 pub struct CustomDNSRecord {
@@ -398,6 +633,17 @@ pub struct CustomCNAMERecord {
     pub target_domain: String,
 }
 
+/// Static DHCP lease, from a `dhcp-host=` line of the dnsmasq config
+#[derive(Deserialize, Serialize, Debug)]
+pub struct StaticDhcpLease {
+    /// MAC address of the client
+    pub mac_address: String,
+    /// IP address assigned to the client
+    pub ip_address: Option<IpAddr>,
+    /// Hostname assigned to the client
+    pub hostname: Option<String>,
+}
+
 /// Response format when requesting information from the FTL while it is not running
 #[derive(Deserialize, Serialize, Debug)]
 pub struct FTLNotRunning {
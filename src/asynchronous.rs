@@ -0,0 +1,754 @@
+//! Asynchronous versions of the Pi Hole API traits.
+//!
+//! Enabled with the `async` feature. The methods mirror [`UnauthenticatedPiHoleAPI`](crate::UnauthenticatedPiHoleAPI)
+//! and [`AuthenticatedPiHoleAPI`](crate::AuthenticatedPiHoleAPI) and are implemented for the same config structs.
+use crate::api_types::*;
+use crate::capabilities::Feature;
+use crate::errors;
+use crate::fake_hash_map::FakeHashMap;
+use crate::query_log::QueryFilter;
+use crate::retry::RetryPolicy;
+use crate::transport::{AsyncHttpTransport, HttpMethod, HttpRequest, HttpResponse};
+use crate::{
+    api_request, authenticated_request, parse_custom_cname_record, parse_custom_dns_record,
+    parse_json_response, parse_recent_blocked, parse_text_response, require_feature, take_field,
+    time_range_params, timestamp_to_naive_datetime, PiHoleAPIHost, PiHoleAPIKey, NO_PARAMS,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serde::de::DeserializeOwned;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+#[async_trait]
+pub trait AsyncUnauthenticatedPiHoleAPI {
+    /// Get statistics in a raw format (no number format)
+    async fn get_summary_raw(&self) -> Result<SummaryRaw, errors::APIError>;
+
+    /// Get statistics in a formatted style
+    async fn get_summary(&self) -> Result<Summary, errors::APIError>;
+
+    /// Get statistics on the number of domains and ads for each 10 minute period
+    async fn get_over_time_data_10_mins(&self) -> Result<OverTimeData, errors::APIError>;
+
+    /// Get the Pi-Hole version.
+    async fn get_version(&self) -> Result<u32, errors::APIError>;
+
+    /// Get the detailed Pi-Hole versions for core, FTL and web interface.
+    async fn get_versions(&self) -> Result<Versions, errors::APIError>;
+}
+
+/// Send `request` and return the response, treating error status codes as errors.
+/// Failed attempts are retried according to `retry_policy`.
+/// Errors carry the endpoint and parameters of the request.
+async fn send_request_for_response(
+    transport: &dyn AsyncHttpTransport,
+    request: HttpRequest,
+    retry_policy: &RetryPolicy,
+) -> Result<HttpResponse, errors::APIError> {
+    let mut retry = 0;
+    loop {
+        let result = transport.send(request.clone()).await.and_then(|response| {
+            if response.status >= 400 {
+                return Err(errors::APIErrorKind::HttpStatus(response.status).into());
+            }
+            Ok(response)
+        });
+        match result {
+            Err(error) if retry_policy.should_retry(retry, &error) => {
+                tokio::time::sleep(retry_policy.backoff_with_jitter(retry)).await;
+                retry += 1;
+            }
+            result => return result.map_err(|error| error.with_context(&request, None)),
+        }
+    }
+}
+
+/// Send an unauthenticated request to the PHP API, which also needs `feature`
+async fn simple_json_request<T, I, K, V>(
+    api: &(impl PiHoleAPIHost + Sync),
+    feature: Feature,
+    path_query: &str,
+    params: I,
+) -> Result<T, errors::APIError>
+where
+    T: DeserializeOwned,
+    I: IntoIterator,
+    K: AsRef<str>,
+    V: AsRef<str>,
+    <I as IntoIterator>::Item: Borrow<(K, V)>,
+{
+    let request = api_request(api, HttpMethod::Get, path_query, params)?;
+    require_feature(api, Feature::PhpApi, &request)?;
+    require_feature(api, feature, &request)?;
+    let response = send_request_for_response(
+        api.get_async_transport(),
+        request.clone(),
+        api.get_retry_policy(),
+    )
+    .await?;
+    parse_json_response(&request, &response)
+}
+
+#[async_trait]
+impl<T> AsyncUnauthenticatedPiHoleAPI for T
+where
+    T: PiHoleAPIHost + Sync,
+{
+    async fn get_summary_raw(&self) -> Result<SummaryRaw, errors::APIError> {
+        simple_json_request(self, Feature::PhpApi, "api.php?summaryRaw", &NO_PARAMS).await
+    }
+
+    async fn get_summary(&self) -> Result<Summary, errors::APIError> {
+        simple_json_request(self, Feature::PhpApi, "api.php?summary", &NO_PARAMS).await
+    }
+
+    async fn get_over_time_data_10_mins(&self) -> Result<OverTimeData, errors::APIError> {
+        simple_json_request(
+            self,
+            Feature::PhpApi,
+            "api.php?overTimeData10mins",
+            &NO_PARAMS,
+        )
+        .await
+    }
+
+    async fn get_version(&self) -> Result<u32, errors::APIError> {
+        let raw_version: Version =
+            simple_json_request(self, Feature::PhpApi, "api.php?version", &NO_PARAMS).await?;
+        Ok(raw_version.version)
+    }
+
+    async fn get_versions(&self) -> Result<Versions, errors::APIError> {
+        simple_json_request(self, Feature::Versions, "api.php?versions", &NO_PARAMS).await
+    }
+}
+
+#[async_trait]
+pub trait AsyncAuthenticatedPiHoleAPI {
+    /// Get the top domains and ads and the number of queries for each. Limit the number of items with `count`.
+    async fn get_top_items(&self, count: &Option<u32>) -> Result<TopItems, errors::APIError>;
+
+    /// Get the top clients and the number of queries for each. Limit the number of items with `count`.
+    async fn get_top_clients(&self, count: &Option<u32>) -> Result<TopClients, errors::APIError>;
+
+    /// Get the top clients blocked and the number of queries for each. Limit the number of items with `count`.
+    async fn get_top_clients_blocked(
+        &self,
+        count: Option<u32>,
+    ) -> Result<TopClientsBlocked, errors::APIError>;
+
+    /// Get the percentage of queries forwarded to each target.
+    async fn get_forward_destinations(
+        &self,
+        unsorted: bool,
+    ) -> Result<ForwardDestinations, errors::APIError>;
+
+    /// Get the number of queries per type.
+    async fn get_query_types(&self) -> Result<QueryTypes, errors::APIError>;
+
+    /// Get all DNS query data. Limit the number of items with `count`.
+    async fn get_all_queries(&self, count: u32) -> Result<Vec<Query>, errors::APIError>;
+
+    /// Get the DNS queries matching `filter`.
+    async fn get_queries(&self, filter: &QueryFilter) -> Result<Vec<Query>, errors::APIError>;
+
+    /// Enable the Pi-Hole.
+    async fn enable(&self) -> Result<Status, errors::APIError>;
+
+    /// Disable the Pi-Hole for `seconds` seconds.
+    async fn disable(&self, seconds: u64) -> Result<Status, errors::APIError>;
+
+    /// Get statistics about the DNS cache.
+    async fn get_cache_info(&self) -> Result<CacheInfo, errors::APIError>;
+
+    /// Get hostname and IP for hosts
+    async fn get_client_names(&self) -> Result<Vec<ClientName>, errors::APIError>;
+
+    /// Get queries by client over time. Maps timestamp to the number of queries by clients.
+    /// Order of clients in the Vector is the same as for get_client_names
+    async fn get_over_time_data_clients(
+        &self,
+    ) -> Result<HashMap<String, Vec<u64>>, errors::APIError>;
+
+    /// Get the percentage of queries answered by each forward destination for each 10 minute period.
+    async fn get_over_time_data_forwards(&self) -> Result<OverTimeDataForwards, errors::APIError>;
+
+    /// Get the percentage of A and AAAA queries for each 10 minute period.
+    async fn get_over_time_data_query_types(
+        &self,
+    ) -> Result<OverTimeDataQueryTypes, errors::APIError>;
+
+    /// Get the forward destinations in the order used by `get_over_time_data_forwards`.
+    async fn get_forward_destination_names(
+        &self,
+    ) -> Result<ForwardDestinationNames, errors::APIError>;
+
+    /// Get the most recently blocked domains, newest last. Limit the number of items with `count`.
+    async fn get_recent_blocked(&self, count: Option<u32>)
+        -> Result<Vec<String>, errors::APIError>;
+
+    /// Get information about network clients.
+    async fn get_network(&self) -> Result<Network, errors::APIError>;
+
+    /// Get the total number of queries received.
+    async fn get_queries_count(&self) -> Result<u64, errors::APIError>;
+
+    /// Get the queries stored in the long-term database between `from` and `until` (UTC).
+    async fn get_database_queries(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<Vec<DatabaseQuery>, errors::APIError>;
+
+    /// Get the top clients between `from` and `until` (UTC) from the long-term database.
+    async fn get_database_top_clients(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<TopClients, errors::APIError>;
+
+    /// Get the top domains and their number of queries between `from` and `until` (UTC) from the long-term database.
+    async fn get_database_top_domains(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<HashMap<String, u64>, errors::APIError>;
+
+    /// Get the top blocked domains and their number of queries between `from` and `until` (UTC) from the long-term database.
+    async fn get_database_top_ads(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<HashMap<String, u64>, errors::APIError>;
+
+    /// Get the number of domains and ads for each `interval` between `from` and `until` (UTC) from the long-term database.
+    /// The Pi Hole uses 10 minute intervals if `interval` is 10 seconds or less.
+    async fn get_database_graph_data(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+        interval: Duration,
+    ) -> Result<OverTimeData, errors::APIError>;
+
+    /// Get the size of the long-term database in bytes.
+    async fn get_database_file_size(&self) -> Result<u64, errors::APIError>;
+
+    /// Get the time of the oldest query in the long-term database, or `None` if it is empty.
+    async fn get_database_min_timestamp(&self) -> Result<Option<NaiveDateTime>, errors::APIError>;
+
+    /// Add domains to a custom white/blacklist.
+    async fn list_add(
+        &self,
+        domain: &str,
+        list: DomainList,
+    ) -> Result<ListModificationResponse, errors::APIError>;
+
+    /// Remove domain to a custom white/blacklist.
+    async fn list_remove(
+        &self,
+        domain: &str,
+        list: DomainList,
+    ) -> Result<ListModificationResponse, errors::APIError>;
+
+    /// Get a list of domains on a particular custom white/blacklist
+    async fn list_get_domains(
+        &self,
+        list: DomainList,
+    ) -> Result<Vec<CustomListDomainDetails>, errors::APIError>;
+
+    /// Get a list of custom DNS records
+    async fn get_custom_dns_records(&self) -> Result<Vec<CustomDNSRecord>, errors::APIError>;
+
+    /// Add a custom DNS record
+    async fn add_custom_dns_record(
+        &self,
+        ip: &IpAddr,
+        domain: &str,
+    ) -> Result<ListModificationResponse, errors::APIError>;
+
+    /// Delete a custom DNS record
+    async fn delete_custom_dns_record(
+        &self,
+        ip: &IpAddr,
+        domain: &str,
+    ) -> Result<ListModificationResponse, errors::APIError>;
+
+    /// Get a list of custom CNAME records
+    async fn get_custom_cname_records(&self) -> Result<Vec<CustomCNAMERecord>, errors::APIError>;
+
+    /// Add a custom CNAME record
+    async fn add_custom_cname_record(
+        &self,
+        domain: &str,
+        target_domain: &str,
+    ) -> Result<ListModificationResponse, errors::APIError>;
+
+    /// Delete a custom CNAME record
+    async fn delete_custom_cname_record(
+        &self,
+        domain: &str,
+        target_domain: &str,
+    ) -> Result<ListModificationResponse, errors::APIError>;
+
+    /// Get max logage
+    async fn get_max_logage(&self) -> Result<f32, errors::APIError>;
+}
+
+/// Send an authenticated read-only request, retried according to the retry policy of `api`
+async fn authenticated_json_request<T, I, K, V>(
+    api: &(impl PiHoleAPIHost + PiHoleAPIKey + Sync),
+    path_query: &str,
+    params: I,
+) -> Result<T, errors::APIError>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    authenticated_json_request_with_retries(
+        api,
+        Feature::PhpApi,
+        path_query,
+        params,
+        api.get_retry_policy(),
+    )
+    .await
+}
+
+/// Send an authenticated read-only request for a plain text response,
+/// retried according to the retry policy of `api`
+async fn authenticated_text_request<I, K, V>(
+    api: &(impl PiHoleAPIHost + PiHoleAPIKey + Sync),
+    path_query: &str,
+    params: I,
+) -> Result<String, errors::APIError>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let request = authenticated_request(api, path_query, params)?;
+    require_feature(api, Feature::PhpApi, &request)?;
+    let response = send_request_for_response(
+        api.get_async_transport(),
+        request.clone(),
+        api.get_retry_policy(),
+    )
+    .await?;
+    parse_text_response(&request, &response)
+}
+
+/// Send an authenticated request which modifies the Pi Hole. These requests are never retried.
+async fn authenticated_json_mutation<T, I, K, V>(
+    api: &(impl PiHoleAPIHost + PiHoleAPIKey + Sync),
+    path_query: &str,
+    params: I,
+) -> Result<T, errors::APIError>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    authenticated_json_request_with_retries(
+        api,
+        Feature::PhpApi,
+        path_query,
+        params,
+        &RetryPolicy::NONE,
+    )
+    .await
+}
+
+/// Send an authenticated request to the PHP API, which also needs `feature`,
+/// retried according to `retry_policy`
+async fn authenticated_json_request_with_retries<T, I, K, V>(
+    api: &(impl PiHoleAPIHost + PiHoleAPIKey + Sync),
+    feature: Feature,
+    path_query: &str,
+    params: I,
+    retry_policy: &RetryPolicy,
+) -> Result<T, errors::APIError>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let request = authenticated_request(api, path_query, params)?;
+    require_feature(api, Feature::PhpApi, &request)?;
+    require_feature(api, feature, &request)?;
+    let response =
+        send_request_for_response(api.get_async_transport(), request.clone(), retry_policy).await?;
+    parse_json_response(&request, &response)
+}
+
+#[async_trait]
+impl<T> AsyncAuthenticatedPiHoleAPI for T
+where
+    T: PiHoleAPIHost + PiHoleAPIKey + Sync,
+{
+    async fn get_top_items(&self, count: &Option<u32>) -> Result<TopItems, errors::APIError> {
+        authenticated_json_request(
+            self,
+            "api.php",
+            [("topItems", count.unwrap_or(10).to_string())],
+        )
+        .await
+    }
+
+    async fn get_top_clients(&self, count: &Option<u32>) -> Result<TopClients, errors::APIError> {
+        authenticated_json_request(
+            self,
+            "api.php",
+            [("topClients", count.unwrap_or(10).to_string())],
+        )
+        .await
+    }
+
+    async fn get_top_clients_blocked(
+        &self,
+        count: Option<u32>,
+    ) -> Result<TopClientsBlocked, errors::APIError> {
+        authenticated_json_request(
+            self,
+            "api.php",
+            [("topClientsBlocked", count.unwrap_or(10).to_string())],
+        )
+        .await
+    }
+
+    async fn get_forward_destinations(
+        &self,
+        unsorted: bool,
+    ) -> Result<ForwardDestinations, errors::APIError> {
+        let param_value = if unsorted { "unsorted" } else { "" };
+        authenticated_json_request(self, "api.php", [("getForwardDestinations", param_value)]).await
+    }
+
+    async fn get_query_types(&self) -> Result<QueryTypes, errors::APIError> {
+        authenticated_json_request(self, "api.php", [("getQueryTypes", "")]).await
+    }
+
+    async fn get_all_queries(&self, count: u32) -> Result<Vec<Query>, errors::APIError> {
+        let mut raw_data: HashMap<String, Vec<Query>> = authenticated_json_request_with_retries(
+            self,
+            Feature::ExtendedQueryLog,
+            "api.php",
+            [("getAllQueries", count.to_string())],
+            self.get_retry_policy(),
+        )
+        .await?;
+        take_field(&mut raw_data, "data")
+    }
+
+    async fn get_queries(&self, filter: &QueryFilter) -> Result<Vec<Query>, errors::APIError> {
+        let mut raw_data: HashMap<String, Vec<Query>> = authenticated_json_request_with_retries(
+            self,
+            Feature::ExtendedQueryLog,
+            "api.php",
+            filter.params(),
+            self.get_retry_policy(),
+        )
+        .await?;
+        Ok(filter.apply(take_field(&mut raw_data, "data")?))
+    }
+
+    async fn enable(&self) -> Result<Status, errors::APIError> {
+        authenticated_json_mutation(self, "api.php", [("enable", "")]).await
+    }
+
+    async fn disable(&self, seconds: u64) -> Result<Status, errors::APIError> {
+        authenticated_json_mutation(self, "api.php", [("disable", seconds.to_string())]).await
+    }
+
+    async fn get_cache_info(&self) -> Result<CacheInfo, errors::APIError> {
+        let mut raw_data: HashMap<String, CacheInfo> =
+            authenticated_json_request(self, "api.php", [("getCacheInfo", "")]).await?;
+        take_field(&mut raw_data, "cacheinfo")
+    }
+
+    async fn get_client_names(&self) -> Result<Vec<ClientName>, errors::APIError> {
+        let mut raw_data: HashMap<String, Vec<ClientName>> =
+            authenticated_json_request(self, "api.php", [("getClientNames", "")]).await?;
+        take_field(&mut raw_data, "clients")
+    }
+
+    async fn get_over_time_data_clients(
+        &self,
+    ) -> Result<HashMap<String, Vec<u64>>, errors::APIError> {
+        let mut raw_data: HashMap<String, FakeHashMap<String, Vec<u64>>> =
+            authenticated_json_request(self, "api.php", [("overTimeDataClients", "")]).await?;
+
+        Ok(take_field(&mut raw_data, "over_time")?.into())
+    }
+
+    async fn get_over_time_data_forwards(&self) -> Result<OverTimeDataForwards, errors::APIError> {
+        authenticated_json_request(self, "api.php", [("overTimeDataForwards", "")]).await
+    }
+
+    async fn get_over_time_data_query_types(
+        &self,
+    ) -> Result<OverTimeDataQueryTypes, errors::APIError> {
+        authenticated_json_request(self, "api.php", [("overTimeDataQueryTypes", "")]).await
+    }
+
+    async fn get_forward_destination_names(
+        &self,
+    ) -> Result<ForwardDestinationNames, errors::APIError> {
+        authenticated_json_request(self, "api.php", [("getForwardDestinationNames", "")]).await
+    }
+
+    async fn get_recent_blocked(
+        &self,
+        count: Option<u32>,
+    ) -> Result<Vec<String>, errors::APIError> {
+        let count = count.map(|count| count.to_string()).unwrap_or_default();
+        let response_text =
+            authenticated_text_request(self, "api.php", [("recentBlocked", count)]).await?;
+        Ok(parse_recent_blocked(&response_text))
+    }
+
+    async fn get_network(&self) -> Result<Network, errors::APIError> {
+        authenticated_json_request(self, "api_db.php", [("network", "")]).await
+    }
+
+    async fn get_queries_count(&self) -> Result<u64, errors::APIError> {
+        let mut raw_data: HashMap<String, u64> =
+            authenticated_json_request(self, "api_db.php", [("getQueriesCount", "")]).await?;
+        take_field(&mut raw_data, "count")
+    }
+
+    async fn get_database_queries(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<Vec<DatabaseQuery>, errors::APIError> {
+        let mut raw_data: HashMap<String, Vec<DatabaseQuery>> = authenticated_json_request(
+            self,
+            "api_db.php",
+            time_range_params("getAllQueries", from, until),
+        )
+        .await?;
+        take_field(&mut raw_data, "data")
+    }
+
+    async fn get_database_top_clients(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<TopClients, errors::APIError> {
+        authenticated_json_request(
+            self,
+            "api_db.php",
+            time_range_params("topClients", from, until),
+        )
+        .await
+    }
+
+    async fn get_database_top_domains(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<HashMap<String, u64>, errors::APIError> {
+        let mut raw_data: HashMap<String, FakeHashMap<String, u64>> = authenticated_json_request(
+            self,
+            "api_db.php",
+            time_range_params("topDomains", from, until),
+        )
+        .await?;
+        Ok(take_field(&mut raw_data, "top_domains")?.into())
+    }
+
+    async fn get_database_top_ads(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<HashMap<String, u64>, errors::APIError> {
+        let mut raw_data: HashMap<String, FakeHashMap<String, u64>> = authenticated_json_request(
+            self,
+            "api_db.php",
+            time_range_params("topAds", from, until),
+        )
+        .await?;
+        Ok(take_field(&mut raw_data, "top_ads")?.into())
+    }
+
+    async fn get_database_graph_data(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+        interval: Duration,
+    ) -> Result<OverTimeData, errors::APIError> {
+        let mut params = time_range_params("getGraphData", from, until);
+        params.push(("interval", interval.as_secs().to_string()));
+        authenticated_json_request(self, "api_db.php", params).await
+    }
+
+    async fn get_database_file_size(&self) -> Result<u64, errors::APIError> {
+        let mut raw_data: HashMap<String, u64> =
+            authenticated_json_request(self, "api_db.php", [("getDBfilesize", "")]).await?;
+        take_field(&mut raw_data, "filesize")
+    }
+
+    async fn get_database_min_timestamp(&self) -> Result<Option<NaiveDateTime>, errors::APIError> {
+        let mut raw_data: HashMap<String, Option<f64>> =
+            authenticated_json_request(self, "api_db.php", [("getMinTimestamp", "")]).await?;
+        take_field(&mut raw_data, "mintimestamp")?
+            .map(timestamp_to_naive_datetime)
+            .transpose()
+    }
+
+    async fn list_add(
+        &self,
+        domain: &str,
+        list: DomainList,
+    ) -> Result<ListModificationResponse, errors::APIError> {
+        authenticated_json_mutation(self, "api.php", [("add", domain), ("list", list.as_str())])
+            .await
+    }
+
+    async fn list_remove(
+        &self,
+        domain: &str,
+        list: DomainList,
+    ) -> Result<ListModificationResponse, errors::APIError> {
+        authenticated_json_mutation(self, "api.php", [("sub", domain), ("list", list.as_str())])
+            .await
+    }
+
+    async fn list_get_domains(
+        &self,
+        list: DomainList,
+    ) -> Result<Vec<CustomListDomainDetails>, errors::APIError> {
+        // if not "add" or "sub", api.php defaults to the "get_domains" action
+        let mut raw_data: HashMap<String, Vec<CustomListDomainDetails>> =
+            authenticated_json_request(self, "api.php", [("get", ""), ("list", list.as_str())])
+                .await?;
+        take_field(&mut raw_data, "data")
+    }
+
+    async fn get_custom_dns_records(&self) -> Result<Vec<CustomDNSRecord>, errors::APIError> {
+        let mut raw_data: HashMap<String, Vec<Vec<String>>> =
+            authenticated_json_request_with_retries(
+                self,
+                Feature::CustomDns,
+                "api.php",
+                [("customdns", ""), ("action", "get")],
+                self.get_retry_policy(),
+            )
+            .await?;
+
+        take_field(&mut raw_data, "data")?
+            .into_iter()
+            .map(parse_custom_dns_record)
+            .collect()
+    }
+
+    async fn add_custom_dns_record(
+        &self,
+        ip: &IpAddr,
+        domain: &str,
+    ) -> Result<ListModificationResponse, errors::APIError> {
+        authenticated_json_request_with_retries(
+            self,
+            Feature::CustomDns,
+            "api.php",
+            [
+                ("customdns", ""),
+                ("action", "add"),
+                ("ip", &ip.to_string()),
+                ("domain", domain),
+            ],
+            &RetryPolicy::NONE,
+        )
+        .await
+    }
+
+    async fn delete_custom_dns_record(
+        &self,
+        ip: &IpAddr,
+        domain: &str,
+    ) -> Result<ListModificationResponse, errors::APIError> {
+        authenticated_json_request_with_retries(
+            self,
+            Feature::CustomDns,
+            "api.php",
+            [
+                ("customdns", ""),
+                ("action", "delete"),
+                ("ip", &ip.to_string()),
+                ("domain", domain),
+            ],
+            &RetryPolicy::NONE,
+        )
+        .await
+    }
+
+    async fn get_custom_cname_records(&self) -> Result<Vec<CustomCNAMERecord>, errors::APIError> {
+        let mut raw_data: HashMap<String, Vec<Vec<String>>> =
+            authenticated_json_request_with_retries(
+                self,
+                Feature::CustomCname,
+                "api.php",
+                [("customcname", ""), ("action", "get")],
+                self.get_retry_policy(),
+            )
+            .await?;
+
+        take_field(&mut raw_data, "data")?
+            .into_iter()
+            .map(parse_custom_cname_record)
+            .collect()
+    }
+
+    async fn add_custom_cname_record(
+        &self,
+        domain: &str,
+        target_domain: &str,
+    ) -> Result<ListModificationResponse, errors::APIError> {
+        authenticated_json_request_with_retries(
+            self,
+            Feature::CustomCname,
+            "api.php",
+            [
+                ("customcname", ""),
+                ("action", "add"),
+                ("domain", domain),
+                ("target", target_domain),
+            ],
+            &RetryPolicy::NONE,
+        )
+        .await
+    }
+
+    async fn delete_custom_cname_record(
+        &self,
+        domain: &str,
+        target_domain: &str,
+    ) -> Result<ListModificationResponse, errors::APIError> {
+        authenticated_json_request_with_retries(
+            self,
+            Feature::CustomCname,
+            "api.php",
+            [
+                ("customcname", ""),
+                ("action", "delete"),
+                ("domain", domain),
+                ("target", target_domain),
+            ],
+            &RetryPolicy::NONE,
+        )
+        .await
+    }
+
+    async fn get_max_logage(&self) -> Result<f32, errors::APIError> {
+        let mut raw_data: HashMap<String, f32> =
+            authenticated_json_request(self, "api.php", [("getMaxlogage", "")]).await?;
+        take_field(&mut raw_data, "maxlogage")
+    }
+}
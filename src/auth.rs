@@ -0,0 +1,88 @@
+//! Credentials used to authenticate with the Pi Hole.
+use crate::errors::{APIError, APIErrorKind};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use zeroize::Zeroize;
+
+/// String holding a secret such as the API key.
+///
+/// The value is redacted from `Debug` output and overwritten with zeros when dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    /// Get the secret value
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// Where the API key is placed in authenticated requests
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ApiKeyLocation {
+    /// Send the key as the `auth` query parameter of a GET request.
+    /// The key may be written to proxy and web server access logs.
+    #[default]
+    QueryString,
+    /// Send the key as the `auth` field of a form encoded POST body,
+    /// keeping it out of the URL
+    PostBody,
+}
+
+/// Derive the API token from the web interface password.
+/// The token is the hex encoded SHA-256 of the hex encoded SHA-256 of the password.
+pub fn api_token_from_password(password: &str) -> SecretString {
+    let mut password_hash = sha256_hex(password.as_bytes());
+    let token = sha256_hex(password_hash.as_bytes());
+    password_hash.zeroize();
+    SecretString::new(token)
+}
+
+/// Read the API token from the `WEBPASSWORD` setting of a `setupVars.conf` file,
+/// usually found at `/etc/pihole/setupVars.conf`.
+/// Returns [`APIErrorKind::MissingAPIKey`] if no web password is set.
+pub fn read_setup_vars_api_token(path: impl AsRef<Path>) -> Result<SecretString, APIError> {
+    let mut contents = fs::read_to_string(path)?;
+    // The last assignment wins if the setting appears more than once
+    let token = contents
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("WEBPASSWORD="))
+        .map(|value| value.trim().trim_matches(['"', '\'']).to_string());
+    contents.zeroize();
+    match token {
+        Some(token) if !token.is_empty() => Ok(SecretString::new(token)),
+        _ => Err(APIErrorKind::MissingAPIKey.into()),
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
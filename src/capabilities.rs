@@ -0,0 +1,247 @@
+//! Detection of the Pi-hole generation and component versions of a host.
+//!
+//! Endpoints differ between Pi-hole releases. Attach detected [`Capabilities`] to a config with
+//! `with_capabilities` to have unsupported calls fail with [`APIErrorKind::Unsupported`]
+//! instead of a deserialization error.
+use crate::api_types::{Version, Versions};
+use crate::errors::{APIError, APIErrorKind};
+use crate::transport::{HttpMethod, HttpRequest};
+use crate::{
+    api_request, parse_json_response, send_request, send_request_for_response, v6_api_path,
+    PiHoleAPIHost, NO_PARAMS,
+};
+use std::fmt;
+
+/// Generation of the Pi-hole API
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiGeneration {
+    /// Pi-hole v5 and earlier, serving the PHP API at `/admin/api.php`
+    V5,
+    /// Pi-hole v6, serving the REST API at `/api`
+    V6,
+}
+
+/// Version of a Pi-hole component, e.g. `v5.18.2`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ComponentVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ComponentVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse a version such as `v5.18.2` or `5.18`.
+    /// Returns `None` for development builds and other versions which are not numeric.
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim().trim_start_matches('v').split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+        let patch = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for ComponentVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Version dependent features of the Pi-hole API
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feature {
+    /// The PHP API at `/admin/api.php`, removed in Pi-hole v6
+    PhpApi,
+    /// The REST API at `/api`, added in Pi-hole v6
+    RestApi,
+    /// Detailed component versions from `api.php?versions`
+    Versions,
+    /// Custom DNS records, added in web interface v5.1
+    CustomDns,
+    /// Custom CNAME records, added in web interface v5.2
+    CustomCname,
+    /// Query log entries with the upstream destination and extended DNS error, added in FTL v5.20
+    ExtendedQueryLog,
+}
+
+/// Pi-hole component a feature depends on
+#[derive(Clone, Copy)]
+enum Component {
+    Core,
+    Web,
+    Ftl,
+}
+
+impl Component {
+    fn name(&self) -> &'static str {
+        match self {
+            Component::Core => "core",
+            Component::Web => "web interface",
+            Component::Ftl => "FTL",
+        }
+    }
+}
+
+impl Feature {
+    /// Minimum component version of a v5 install supporting the feature
+    fn minimum_v5_version(&self) -> Option<(Component, ComponentVersion)> {
+        match self {
+            Feature::PhpApi | Feature::RestApi => None,
+            Feature::Versions => Some((Component::Core, ComponentVersion::new(5, 0, 0))),
+            Feature::CustomDns => Some((Component::Web, ComponentVersion::new(5, 1, 0))),
+            Feature::CustomCname => Some((Component::Web, ComponentVersion::new(5, 2, 0))),
+            Feature::ExtendedQueryLog => Some((Component::Ftl, ComponentVersion::new(5, 20, 0))),
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Feature::PhpApi => "the PHP API",
+            Feature::RestApi => "the REST API",
+            Feature::Versions => "detailed versions",
+            Feature::CustomDns => "custom DNS records",
+            Feature::CustomCname => "custom CNAME records",
+            Feature::ExtendedQueryLog => "the extended query log",
+        })
+    }
+}
+
+/// Generation and component versions of a Pi-hole host
+#[derive(Clone, Debug, PartialEq)]
+pub struct Capabilities {
+    /// API generation
+    pub generation: ApiGeneration,
+    /// Pi-hole core version, if known
+    pub core_version: Option<ComponentVersion>,
+    /// Web interface version, if known
+    pub web_version: Option<ComponentVersion>,
+    /// FTL version, if known
+    pub ftl_version: Option<ComponentVersion>,
+}
+
+impl Capabilities {
+    /// Capabilities of a host of the given generation with unknown component versions
+    pub fn new(generation: ApiGeneration) -> Self {
+        Self {
+            generation,
+            core_version: None,
+            web_version: None,
+            ftl_version: None,
+        }
+    }
+
+    /// Capabilities of a host reporting `versions`
+    pub fn from_versions(generation: ApiGeneration, versions: &Versions) -> Self {
+        Self {
+            generation,
+            core_version: ComponentVersion::parse(&versions.core_current),
+            web_version: ComponentVersion::parse(&versions.web_current),
+            ftl_version: ComponentVersion::parse(&versions.ftl_current),
+        }
+    }
+
+    /// Whether the host supports `feature`.
+    /// Features are assumed to be supported when the relevant component version is unknown.
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.require(feature).is_ok()
+    }
+
+    /// Return [`APIErrorKind::Unsupported`] if the host does not support `feature`
+    pub fn require(&self, feature: Feature) -> Result<(), APIError> {
+        match (feature, self.generation) {
+            (Feature::PhpApi, ApiGeneration::V6) => {
+                return Err(APIErrorKind::Unsupported(
+                    "The PHP API is not available on Pi-hole v6, use the v6 module instead"
+                        .to_string(),
+                )
+                .into())
+            }
+            (Feature::RestApi, ApiGeneration::V5) => {
+                return Err(APIErrorKind::Unsupported(
+                    "The REST API requires Pi-hole v6".to_string(),
+                )
+                .into())
+            }
+            (_, ApiGeneration::V6) => return Ok(()),
+            _ => {}
+        }
+        let (component, minimum) = match feature.minimum_v5_version() {
+            Some(minimum) => minimum,
+            None => return Ok(()),
+        };
+        let version = match component {
+            Component::Core => self.core_version,
+            Component::Web => self.web_version,
+            Component::Ftl => self.ftl_version,
+        };
+        match version {
+            Some(version) if version < minimum => Err(APIErrorKind::Unsupported(format!(
+                "Using {} requires Pi-hole {} {} or later, found {}",
+                feature,
+                component.name(),
+                minimum,
+                version
+            ))
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Probe `api` for its API generation and component versions
+pub(crate) fn detect(api: &impl PiHoleAPIHost) -> Result<Capabilities, APIError> {
+    // Pi-hole v6 serves the login info without authentication
+    let login_info_url = format!(
+        "{}{}/info/login",
+        api.get_host(),
+        v6_api_path(api.get_base_path())
+    );
+    let login_info_url = url::Url::parse(&login_info_url).map_err(|error| {
+        APIError::from(APIErrorKind::InvalidUrl(format!(
+            "{}: {}",
+            login_info_url, error
+        )))
+        .with_url_context(HttpMethod::Get, &login_info_url)
+    })?;
+    let login_info = send_request(
+        api.get_transport(),
+        HttpRequest::get(login_info_url),
+        api.get_retry_policy(),
+    );
+    if let Ok(login_info) = login_info {
+        if let Ok(serde_json::Value::Object(_)) = serde_json::from_str(&login_info) {
+            return Ok(Capabilities::new(ApiGeneration::V6));
+        }
+    }
+
+    let versions = send_request(
+        api.get_transport(),
+        api_request(api, HttpMethod::Get, "api.php?versions", &NO_PARAMS)?,
+        api.get_retry_policy(),
+    )
+    .and_then(|text| Ok(serde_json::from_str::<Versions>(&text)?));
+    match versions {
+        Ok(versions) => Ok(Capabilities::from_versions(ApiGeneration::V5, &versions)),
+        Err(_) => {
+            // Older releases only report the API version
+            let request = api_request(api, HttpMethod::Get, "api.php?version", &NO_PARAMS)?;
+            let response = send_request_for_response(
+                api.get_transport(),
+                request.clone(),
+                api.get_retry_policy(),
+            )?;
+            parse_json_response::<Version>(&request, &response)?;
+            Ok(Capabilities::new(ApiGeneration::V5))
+        }
+    }
+}
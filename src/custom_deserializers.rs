@@ -1,7 +1,11 @@
+use crate::fake_hash_map::FakeHashMap;
 use crate::ftl_types::*;
-use chrono::NaiveDateTime;
-use num_traits::FromPrimitive;
-use serde::{de::Deserializer, Deserialize};
+use chrono::{DateTime, NaiveDateTime};
+use serde::de::{Deserializer, Error, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::marker::PhantomData;
 use std::time::Duration;
 
 /// Deserialize to integer and then convert into a boolean
@@ -10,7 +14,14 @@ pub fn deserialize_uint_to_bool<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<bool, D::Error> {
     let result = u64::deserialize(deserializer)?;
-    Ok(result == 0)
+    Ok(result != 0)
+}
+
+/// Deserialize `null` as the default value, e.g. an empty string
+pub fn deserialize_null_to_default<'de, D: Deserializer<'de>, T: Default + Deserialize<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
 }
 
 /// Deserialize to string and then convert into an i32
@@ -19,7 +30,9 @@ pub fn deserialize_string_to_i32<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<i32, D::Error> {
     let result = String::deserialize(deserializer)?;
-    Ok(result.parse::<i32>().unwrap())
+    result
+        .parse::<i32>()
+        .map_err(|_| D::Error::invalid_value(Unexpected::Str(&result), &"an integer string"))
 }
 
 /// Deserialize to a string first (format is "1656247185")
@@ -29,10 +42,12 @@ pub fn deserialize_string_to_naive_datetime<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<NaiveDateTime, D::Error> {
     let result = String::deserialize(deserializer)?;
-    Ok(NaiveDateTime::from_timestamp(
-        result.parse::<i64>().unwrap(),
-        0,
-    ))
+    result
+        .parse::<i64>()
+        .ok()
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map(|datetime| datetime.naive_utc())
+        .ok_or_else(|| D::Error::invalid_value(Unexpected::Str(&result), &"a Unix timestamp"))
 }
 
 /// Deserialize to a string, then convert to a u8 and finally to a DNSSECStatus
@@ -40,9 +55,7 @@ pub fn deserialize_string_to_naive_datetime<'de, D: Deserializer<'de>>(
 pub fn deserialize_string_to_dnssec_status<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<DNSSECStatus, D::Error> {
-    let string = String::deserialize(deserializer)?;
-    let u8_value = string.parse::<u8>().unwrap();
-    Ok(FromPrimitive::from_u8(u8_value).unwrap())
+    deserialize_string_to_primitive_enum(deserializer, "a DNSSEC status")
 }
 
 /// Deserialize to a string, then convert to a u8 and finally to a QueryStatus
@@ -50,9 +63,7 @@ pub fn deserialize_string_to_dnssec_status<'de, D: Deserializer<'de>>(
 pub fn deserialize_string_to_query_status<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<QueryStatus, D::Error> {
-    let string = String::deserialize(deserializer)?;
-    let u8_value = string.parse::<u8>().unwrap();
-    Ok(FromPrimitive::from_u8(u8_value).unwrap())
+    deserialize_string_to_primitive_enum(deserializer, "a query status")
 }
 
 /// Deserialize to a string, then convert to a u64 of unit 100us and finally to a Duration
@@ -61,8 +72,13 @@ pub fn deserialize_string_to_duration_100_microseconds<'de, D: Deserializer<'de>
     deserializer: D,
 ) -> Result<Duration, D::Error> {
     let string = String::deserialize(deserializer)?;
-    let duration_in_100us = string.parse::<u64>().unwrap();
-    Ok(Duration::from_micros(100 * duration_in_100us))
+    let duration_in_100us = string
+        .parse::<u64>()
+        .map_err(|_| D::Error::invalid_value(Unexpected::Str(&string), &"an integer string"))?;
+    let micros = duration_in_100us
+        .checked_mul(100)
+        .ok_or_else(|| D::Error::invalid_value(Unexpected::Str(&string), &"a duration in range"))?;
+    Ok(Duration::from_micros(micros))
 }
 
 // /// Deserialize to a string, then convert to a u8 and finally to a QueryType
@@ -80,7 +96,71 @@ pub fn deserialize_string_to_duration_100_microseconds<'de, D: Deserializer<'de>
 pub fn deserialize_string_to_reply_type<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<ReplyType, D::Error> {
+    deserialize_string_to_primitive_enum(deserializer, "a reply type")
+}
+
+/// Deserialize a map keyed by timestamp strings (format is "1656247185") to a map sorted by time.
+/// An empty list is treated as an empty map.
+pub fn deserialize_timestamp_map<'de, D: Deserializer<'de>, V: Deserialize<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<NaiveDateTime, V>, D::Error> {
+    let map: HashMap<String, V> = FakeHashMap::deserialize(deserializer)?.into();
+    map.into_iter()
+        .map(|(timestamp, value)| {
+            let datetime = timestamp
+                .parse::<i64>()
+                .ok()
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                .ok_or_else(|| {
+                    D::Error::invalid_value(Unexpected::Str(&timestamp), &"a Unix timestamp")
+                })?;
+            Ok((datetime.naive_utc(), value))
+        })
+        .collect()
+}
+
+/// Deserialize a map to a list of its entries in the order they were received.
+/// An empty list is treated as an empty map.
+pub fn deserialize_ordered_map<'de, D: Deserializer<'de>, V: Deserialize<'de>>(
+    deserializer: D,
+) -> Result<Vec<(String, V)>, D::Error> {
+    struct OrderedMapVisitor<V>(PhantomData<V>);
+
+    impl<'de, V: Deserialize<'de>> Visitor<'de> for OrderedMapVisitor<V> {
+        type Value = Vec<(String, V)>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entries = Vec::new();
+            while let Some(entry) = map.next_entry()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            match seq.next_element::<IgnoredAny>()? {
+                Some(_) => Err(A::Error::invalid_type(Unexpected::Seq, &self)),
+                None => Ok(Vec::new()),
+            }
+        }
+    }
+
+    deserializer.deserialize_any(OrderedMapVisitor(PhantomData))
+}
+
+/// Deserialize to a string, then convert to a u8 and finally to the enum variant with that value.
+/// `expected` describes the enum in the error for values which are not a u8.
+fn deserialize_string_to_primitive_enum<'de, D: Deserializer<'de>, T: From<u8>>(
+    deserializer: D,
+    expected: &'static str,
+) -> Result<T, D::Error> {
     let string = String::deserialize(deserializer)?;
-    let u8_value = string.parse::<u8>().unwrap();
-    Ok(FromPrimitive::from_u8(u8_value).unwrap())
+    string
+        .parse::<u8>()
+        .map(T::from)
+        .map_err(|_| D::Error::invalid_value(Unexpected::Str(&string), &expected))
 }
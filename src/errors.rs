@@ -1,45 +1,357 @@
 use crate::api_types::FTLNotRunning;
+use crate::transport::{HttpMethod, HttpRequest};
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
 use std::io;
 
+/// Query parameters and form fields whose values are replaced in error messages
+const REDACTED_PARAMS: [&str; 5] = ["auth", "token", "pw", "password", "sid"];
+
+/// Error of a call to the Pi Hole, with the endpoint and parameters of the request which failed, if any.
+/// Use [`kind`](APIError::kind) to match on the underlying error.
+#[derive(Debug)]
+pub struct APIError {
+    kind: Box<APIErrorKind>,
+    context: Option<Box<ErrorContext>>,
+}
+
+/// Underlying error of an [`APIError`]
 #[derive(Debug)]
-pub enum APIError {
-    RequestError(ureq::Error),
+pub enum APIErrorKind {
+    RequestError(RequestError),
+    #[cfg(feature = "async")]
+    AsyncRequestError(reqwest::Error),
     IntoJsonError(io::Error),
     SerdeJSONError(serde_json::Error),
+    TransportError(Box<dyn std::error::Error + Send + Sync>),
+    HttpStatus(u16),
+    InvalidTlsConfig(String),
+    InvalidUrl(String),
+    InvalidResponse(String),
+    /// The response did not include the named field
+    MissingField(String),
     MissingAPIKey,
+    Unauthorized,
+    ServerError(String),
+    LoginFailed,
+    Unsupported(String),
     InvalidList,
     FTLNotRunning,
 }
 
+impl APIError {
+    /// Attach the endpoint and parameters of `request` to the error, unless it already has a context.
+    /// `status` is the status code of the response, if one was received.
+    pub(crate) fn with_context(mut self, request: &HttpRequest, status: Option<u16>) -> Self {
+        if self.context.is_none() {
+            let status = match *self.kind {
+                APIErrorKind::HttpStatus(status) => Some(status),
+                _ => status,
+            };
+            self.context = Some(Box::new(ErrorContext::new(request, status)));
+        }
+        self
+    }
+
+    /// Attach the endpoint of a `method` request to `url`, which could not be parsed, to the error
+    pub(crate) fn with_url_context(mut self, method: HttpMethod, url: &str) -> Self {
+        if self.context.is_none() {
+            self.context = Some(Box::new(ErrorContext::from_parts(method, url, &[], None)));
+        }
+        self
+    }
+
+    /// Get the underlying error
+    pub fn kind(&self) -> &APIErrorKind {
+        &self.kind
+    }
+
+    /// Take the underlying error, dropping the request context
+    pub fn into_kind(self) -> APIErrorKind {
+        *self.kind
+    }
+
+    /// Get the request context of the error, if any
+    pub fn context(&self) -> Option<&ErrorContext> {
+        self.context.as_deref()
+    }
+
+    /// Get the HTTP status code of the response which caused the error, if a response was received
+    pub fn status(&self) -> Option<u16> {
+        match (&self.context, self.kind()) {
+            (Some(context), _) => context.status,
+            (None, APIErrorKind::HttpStatus(status)) => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for APIError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.context {
+            Some(context) => write!(f, "{} failed", context),
+            None => self.kind.fmt(f),
+        }
+    }
+}
+
+impl Error for APIError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.context {
+            Some(_) => Some(self.kind.as_ref()),
+            None => self.kind.source(),
+        }
+    }
+}
+
+impl From<APIErrorKind> for APIError {
+    fn from(kind: APIErrorKind) -> Self {
+        Self {
+            kind: Box::new(kind),
+            context: None,
+        }
+    }
+}
+
+impl fmt::Display for APIErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            APIErrorKind::RequestError(_) => f.write_str("HTTP request failed"),
+            #[cfg(feature = "async")]
+            APIErrorKind::AsyncRequestError(_) => f.write_str("HTTP request failed"),
+            APIErrorKind::IntoJsonError(_) => f.write_str("Failed to read the response"),
+            APIErrorKind::SerdeJSONError(_) => f.write_str("Failed to parse the response"),
+            APIErrorKind::TransportError(_) => f.write_str("Transport failed to send the request"),
+            APIErrorKind::HttpStatus(status) => write!(f, "Server returned HTTP status {}", status),
+            APIErrorKind::InvalidTlsConfig(message) => {
+                write!(f, "Invalid TLS configuration: {}", message)
+            }
+            APIErrorKind::InvalidUrl(message) => write!(f, "Invalid URL {}", message),
+            APIErrorKind::InvalidResponse(message) => write!(f, "Invalid response: {}", message),
+            APIErrorKind::MissingField(field) => {
+                write!(f, "Response is missing the {} field", field)
+            }
+            APIErrorKind::MissingAPIKey => f.write_str("No API key set"),
+            APIErrorKind::Unauthorized => f.write_str("Unauthorized, check the API key"),
+            APIErrorKind::ServerError(message) => write!(f, "Server error: {}", message),
+            APIErrorKind::LoginFailed => f.write_str("Login failed, check the password"),
+            APIErrorKind::Unsupported(message) => f.write_str(message),
+            APIErrorKind::InvalidList => f.write_str("Invalid list"),
+            APIErrorKind::FTLNotRunning => f.write_str("FTL is not running"),
+        }
+    }
+}
+
+impl Error for APIErrorKind {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            APIErrorKind::RequestError(error) => Some(error),
+            #[cfg(feature = "async")]
+            APIErrorKind::AsyncRequestError(error) => Some(error),
+            APIErrorKind::IntoJsonError(error) => Some(error),
+            APIErrorKind::SerdeJSONError(error) => Some(error),
+            APIErrorKind::TransportError(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Endpoint and parameters of a failed request, with secrets redacted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Request method
+    pub method: HttpMethod,
+    /// URL of the endpoint without the query string
+    pub endpoint: String,
+    /// Query parameters and form fields of the request
+    pub params: Vec<(String, String)>,
+    /// Status code of the response, if one was received
+    pub status: Option<u16>,
+}
+
+impl ErrorContext {
+    fn new(request: &HttpRequest, status: Option<u16>) -> Self {
+        let is_form = request.headers.iter().any(|(name, value)| {
+            name.eq_ignore_ascii_case("Content-Type")
+                && value.starts_with("application/x-www-form-urlencoded")
+        });
+        let form = match (&request.body, is_form) {
+            (Some(body), true) => body.as_slice(),
+            _ => &[],
+        };
+        Self::from_parts(request.method, request.url.as_str(), form, status)
+    }
+
+    /// Context of a `method` request to `url` with the form encoded body `form`
+    fn from_parts(method: HttpMethod, url: &str, form: &[u8], status: Option<u16>) -> Self {
+        let (endpoint, query) = url.split_once('?').unwrap_or((url, ""));
+        let params = url::form_urlencoded::parse(query.as_bytes())
+            .chain(url::form_urlencoded::parse(form))
+            .map(|(name, value)| {
+                let value = if REDACTED_PARAMS.contains(&name.as_ref()) {
+                    "[REDACTED]".to_string()
+                } else {
+                    value.into_owned()
+                };
+                (name.into_owned(), value)
+            })
+            .collect();
+        Self {
+            method,
+            endpoint: endpoint.to_string(),
+            params,
+            status,
+        }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.endpoint)?;
+        for (i, (name, value)) in self.params.iter().enumerate() {
+            let separator = if i == 0 { '?' } else { '&' };
+            if value.is_empty() {
+                write!(f, "{}{}", separator, name)?;
+            } else {
+                write!(f, "{}{}={}", separator, name, value)?;
+            }
+        }
+        if let Some(status) = self.status {
+            write!(f, " (HTTP {})", status)?;
+        }
+        Ok(())
+    }
+}
+
+/// Failure of a ureq request. Unlike [`ureq::Error`] it does not keep the request URL,
+/// which includes the API key.
+#[derive(Debug)]
+pub struct RequestError {
+    kind: ureq::ErrorKind,
+    message: String,
+}
+
+impl RequestError {
+    /// Get the kind of failure reported by ureq
+    pub fn kind(&self) -> ureq::ErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for RequestError {}
+
+impl From<ureq::Error> for RequestError {
+    fn from(error: ureq::Error) -> Self {
+        let kind = error.kind();
+        let transport = match error {
+            ureq::Error::Status(status, _) => {
+                return Self {
+                    kind,
+                    message: format!("{}: status code {}", kind, status),
+                }
+            }
+            ureq::Error::Transport(transport) => transport,
+        };
+        // Rebuild the message of the transport error without its URL
+        let mut message = kind.to_string();
+        if let Some(detail) = transport.message() {
+            message = format!("{}: {}", message, detail);
+        }
+        if let Some(source) = transport.source() {
+            message = format!("{}: {}", message, source);
+        }
+        // The message may still quote the URL, e.g. when it could not be parsed
+        let secrets = transport.url().into_iter().flat_map(|url| {
+            url.query_pairs()
+                .filter(|(name, value)| {
+                    REDACTED_PARAMS.contains(&name.as_ref()) && !value.is_empty()
+                })
+                .map(|(_, value)| value.into_owned())
+                .collect::<Vec<_>>()
+        });
+        for secret in secrets {
+            message = message.replace(&secret, "[REDACTED]");
+        }
+        Self { kind, message }
+    }
+}
+
 impl From<ureq::Error> for APIError {
     fn from(error: ureq::Error) -> Self {
-        APIError::RequestError(error)
+        APIErrorKind::RequestError(error.into()).into()
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<reqwest::Error> for APIError {
+    fn from(error: reqwest::Error) -> Self {
+        // The URL of the request includes the API key
+        APIErrorKind::AsyncRequestError(error.without_url()).into()
     }
 }
 
 impl From<io::Error> for APIError {
     fn from(error: io::Error) -> Self {
-        APIError::IntoJsonError(error)
+        APIErrorKind::IntoJsonError(error).into()
     }
 }
 
 impl From<serde_json::Error> for APIError {
     fn from(error: serde_json::Error) -> Self {
-        APIError::SerdeJSONError(error)
+        APIErrorKind::SerdeJSONError(error).into()
     }
 }
 
 /// Filter out response errors from the API
 pub fn detect_response_errors(response_text: &str) -> Result<(), APIError> {
-    if response_text.starts_with("Invalid list") {
-        return Err(APIError::InvalidList);
+    let trimmed = response_text.trim();
+    // api.php answers requests with a missing or wrong API key with an empty array
+    if trimmed == "[]" || trimmed.starts_with("Not authorized") {
+        return Err(APIErrorKind::Unauthorized.into());
     }
-    if let Ok(ftl_response) = serde_json::from_str::<FTLNotRunning>(response_text) {
-        if !ftl_response.ftl_not_running {
-            assert!(!ftl_response.ftl_not_running);
-            return Err(APIError::FTLNotRunning);
+    if trimmed.starts_with("Invalid list") {
+        return Err(APIErrorKind::InvalidList.into());
+    }
+    if let Ok(ftl_response) = serde_json::from_str::<FTLNotRunning>(trimmed) {
+        if ftl_response.ftl_not_running {
+            return Err(APIErrorKind::FTLNotRunning.into());
         }
     }
+    if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(trimmed) {
+        return Err(APIErrorKind::ServerError(error_response.error.into_message()).into());
+    }
 
     Ok(())
 }
+
+/// Response with an error message, either `{"error": "message"}` or `{"error": {"message": "message"}}`
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorMessage,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ErrorMessage {
+    Text(String),
+    Object {
+        message: Option<String>,
+        key: Option<String>,
+    },
+}
+
+impl ErrorMessage {
+    fn into_message(self) -> String {
+        match self {
+            ErrorMessage::Text(message) => message,
+            ErrorMessage::Object { message, key } => message.or(key).unwrap_or_default(),
+        }
+    }
+}
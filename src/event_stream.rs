@@ -0,0 +1,136 @@
+//! Reads the output of shell commands streamed by PHP scripts such as `gravity.sh.php`.
+//!
+//! The scripts forward the command output in arbitrary chunks, one server-sent event per chunk,
+//! so lines may be split across events.
+use crate::errors::APIError;
+use crate::transport::HttpStreamingResponse;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+
+/// Replaces the escape sequence used to overwrite the current line
+const OVERWRITE_MARKER: &str = "<------";
+
+/// Iterator over the non-empty output lines of an event stream, without colours
+pub(crate) struct EventStreamLines {
+    reader: BufReader<Box<dyn Read + Send>>,
+    /// Output received after the last complete line
+    output: Vec<u8>,
+    /// Complete lines not yet returned
+    lines: VecDeque<String>,
+    finished: bool,
+}
+
+impl EventStreamLines {
+    pub(crate) fn new(response: HttpStreamingResponse) -> Self {
+        Self {
+            reader: BufReader::new(response.body),
+            output: Vec::new(),
+            lines: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Read the next event into the output. Returns `false` at the end of the stream.
+    fn read_event(&mut self) -> Result<bool, APIError> {
+        let mut data: Option<Vec<u8>> = None;
+        loop {
+            let mut line = Vec::new();
+            if self.reader.read_until(b'\n', &mut line)? == 0 {
+                if let Some(data) = data {
+                    self.push_output(&data);
+                }
+                return Ok(false);
+            }
+            while line.last() == Some(&b'\n') || line.last() == Some(&b'\r') {
+                line.pop();
+            }
+
+            if line.is_empty() {
+                if let Some(data) = data {
+                    self.push_output(&data);
+                    return Ok(true);
+                }
+            } else if let Some(value) = line.strip_prefix(b"data:") {
+                let value = value.strip_prefix(b" ").unwrap_or(value);
+                match &mut data {
+                    Some(data) => {
+                        data.push(b'\n');
+                        data.extend_from_slice(value);
+                    }
+                    None => data = Some(value.to_vec()),
+                }
+            }
+        }
+    }
+
+    /// Append the data of an event to the output, splitting off complete lines
+    fn push_output(&mut self, data: &[u8]) {
+        self.output.extend_from_slice(data);
+        while let Some(end) = self.output.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.output.drain(..=end).collect();
+            self.push_line(&String::from_utf8_lossy(&line[..end]));
+        }
+    }
+
+    fn push_line(&mut self, raw_line: &str) {
+        let line = clean_line(raw_line);
+        if !line.is_empty() {
+            self.lines.push_back(line);
+        }
+    }
+}
+
+impl Iterator for EventStreamLines {
+    type Item = Result<String, APIError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(line) = self.lines.pop_front() {
+                return Some(Ok(line));
+            }
+            if self.finished {
+                return None;
+            }
+            match self.read_event() {
+                Ok(true) => {}
+                Ok(false) => {
+                    self.finished = true;
+                    let rest = std::mem::take(&mut self.output);
+                    self.push_line(&String::from_utf8_lossy(&rest));
+                }
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+/// Remove colours, HTML colour tags and overwritten text from an output line
+fn clean_line(line: &str) -> String {
+    let line = line.rsplit(OVERWRITE_MARKER).next().unwrap_or(line);
+    let line = line.rsplit('\r').next().unwrap_or(line);
+
+    let mut cleaned = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        if c == '\u{1b}' {
+            // Skip `ESC [ parameters letter`
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| c.is_ascii_alphabetic())
+                .map_or(rest.len(), |(index, c)| index + c.len_utf8());
+            rest = &rest[end..];
+        } else if rest.starts_with("<span") || rest.starts_with("</span>") {
+            // Some versions of the web interface turn colours into HTML
+            let end = rest.find('>').map_or(rest.len(), |index| index + 1);
+            rest = &rest[end..];
+        } else {
+            cleaned.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    cleaned.trim().to_string()
+}
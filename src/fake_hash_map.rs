@@ -15,7 +15,7 @@ where
     EmptyList(Vec<V>),
 }
 
-impl<'a, K, V> FakeHashMap<K, V>
+impl<K, V> FakeHashMap<K, V>
 where
     K: std::cmp::Eq + std::hash::Hash,
 {
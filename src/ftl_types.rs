@@ -1,86 +1,226 @@
-use num_derive::FromPrimitive;
-use serde::{Deserialize, Serialize};
-use serde_repr::*;
-use strum::{EnumCount, EnumIter};
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+use strum::{EnumCount, EnumIter, IntoEnumIterator};
 
 // These types are taken from enums.h in the Pi-Hole FTL repository
 // and modified to be able to convert the integer values into something more easily usable
 
-#[derive(
-    Serialize_repr, Deserialize_repr, Eq, PartialEq, Debug, EnumCount, EnumIter, FromPrimitive,
-)]
-#[repr(u8)]
-pub enum DNSSECStatus {
-    DNSSECUnspecified,
-    DNSSECSecure,
-    DNSSECInsecure,
-    DNSSECBogus,
-    DNSSECAbandoned,
+/// Define an FTL enum serialized as its integer value.
+/// Values added in newer FTL releases deserialize to `Unknown` with the raw value.
+macro_rules! ftl_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident = $value:literal,)* }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Eq, PartialEq, Debug, EnumIter)]
+        pub enum $name {
+            $($variant,)*
+            /// Value not known to this version of the crate
+            #[strum(disabled)]
+            Unknown(u8),
+        }
+
+        impl $name {
+            /// Integer value used by FTL
+            pub fn value(&self) -> u8 {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => *value,
+                }
+            }
+        }
+
+        impl From<u8> for $name {
+            fn from(value: u8) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    value => $name::Unknown(value),
+                }
+            }
+        }
+
+        /// Counts the known variants, excluding `Unknown`
+        impl EnumCount for $name {
+            const COUNT: usize = [$($value),*].len();
+        }
+
+        impl FromPrimitive for $name {
+            fn from_i64(value: i64) -> Option<Self> {
+                u8::try_from(value).ok().map(Self::from)
+            }
+
+            fn from_u64(value: u64) -> Option<Self> {
+                u8::try_from(value).ok().map(Self::from)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_u8(self.value())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                u8::deserialize(deserializer).map(Self::from)
+            }
+        }
+    };
+}
+
+ftl_enum!(DNSSECStatus {
+    DNSSECUnspecified = 0,
+    DNSSECSecure = 1,
+    DNSSECInsecure = 2,
+    DNSSECBogus = 3,
+    DNSSECAbandoned = 4,
+});
+
+ftl_enum!(QueryStatus {
+    QueryUnknown = 0,
+    QueryGravity = 1,
+    QueryForwarded = 2,
+    QueryCache = 3,
+    QueryRegex = 4,
+    QueryBlacklist = 5,
+    QueryExternalBlockedIp = 6,
+    QueryExternalBlockedNull = 7,
+    QueryExternalBlockedNxra = 8,
+    QueryGravityCname = 9,
+    QueryRegexCname = 10,
+    QueryBlacklistCname = 11,
+    QueryRetried = 12,
+    QueryRetriedDnssec = 13,
+    QueryInProgress = 14,
+    QueryDbbusy = 15,
+    QueryStatusMax = 16,
+});
+
+impl QueryStatus {
+    /// Whether the query was blocked
+    pub fn is_blocked(&self) -> bool {
+        matches!(
+            self,
+            QueryStatus::QueryGravity
+                | QueryStatus::QueryRegex
+                | QueryStatus::QueryBlacklist
+                | QueryStatus::QueryExternalBlockedIp
+                | QueryStatus::QueryExternalBlockedNull
+                | QueryStatus::QueryExternalBlockedNxra
+                | QueryStatus::QueryGravityCname
+                | QueryStatus::QueryRegexCname
+                | QueryStatus::QueryBlacklistCname
+        )
+    }
 }
 
-#[derive(
-    Serialize_repr, Deserialize_repr, Eq, PartialEq, Debug, EnumCount, EnumIter, FromPrimitive,
-)]
-#[repr(u8)]
-pub enum QueryStatus {
-    QueryUnknown,
-    QueryGravity,
-    QueryForwarded,
-    QueryCache,
-    QueryRegex,
-    QueryBlacklist,
-    QueryExternalBlockedIp,
-    QueryExternalBlockedNull,
-    QueryExternalBlockedNxra,
-    QueryGravityCname,
-    QueryRegexCname,
-    QueryBlacklistCname,
-    QueryRetried,
-    QueryRetriedDnssec,
-    QueryInProgress,
-    QueryDbbusy,
-    QueryStatusMax,
+ftl_enum!(ReplyType {
+    ReplyUNKNOWN = 0,
+    ReplyNODATA = 1,
+    ReplyNXDOMAIN = 2,
+    ReplyCNAME = 3,
+    ReplyIP = 4,
+    ReplyDOMAIN = 5,
+    ReplyRRNAME = 6,
+    ReplySERVFAIL = 7,
+    ReplyREFUSED = 8,
+    ReplyNOTIMP = 9,
+    ReplyOTHER = 10,
+    ReplyDNSSEC = 11,
+    ReplyNONE = 12,
+    ReplyBLOB = 13,
+    QueryReplyMax = 14,
+});
+
+/// Define an FTL enum serialized as the name of its variants.
+/// Names added in newer FTL releases deserialize to `Unknown` with the raw name.
+macro_rules! ftl_name_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident,)* }) => {
+        $(#[$meta])*
+        #[derive(Clone, Eq, PartialEq, Debug, EnumIter)]
+        pub enum $name {
+            $($variant,)*
+            /// Name not known to this version of the crate
+            #[strum(disabled)]
+            Unknown(String),
+        }
+
+        impl $name {
+            /// Name used by FTL
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => stringify!($variant),)*
+                    $name::Unknown(name) => name,
+                }
+            }
+        }
+
+        /// Counts the known variants, excluding `Unknown`
+        impl EnumCount for $name {
+            const COUNT: usize = [$(stringify!($variant)),*].len();
+        }
+    };
+}
+
+ftl_name_enum!(
+    /// DNS query type, serialized as its name. Unknown types are named e.g. `TYPE65`.
+    QueryType {
+        A,
+        AAAA,
+        ANY,
+        SRV,
+        SOA,
+        PTR,
+        TXT,
+        NAPTR,
+        MX,
+        DS,
+        RRSIG,
+        DNSKEY,
+        NS,
+        OTHER,
+        SVCB,
+        HTTPS,
+        MAX,
+    }
+);
+
+impl From<&str> for QueryType {
+    fn from(name: &str) -> Self {
+        QueryType::iter()
+            .find(|query_type| query_type.as_str() == name)
+            .unwrap_or_else(|| QueryType::Unknown(name.to_string()))
+    }
+}
+
+impl fmt::Display for QueryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromPrimitive for QueryType {
+    fn from_i64(value: i64) -> Option<Self> {
+        usize::try_from(value)
+            .ok()
+            .and_then(|index| QueryType::iter().nth(index))
+    }
+
+    fn from_u64(value: u64) -> Option<Self> {
+        usize::try_from(value)
+            .ok()
+            .and_then(|index| QueryType::iter().nth(index))
+    }
 }
 
-#[derive(
-    Serialize_repr, Deserialize_repr, Eq, PartialEq, Debug, EnumCount, EnumIter, FromPrimitive,
-)]
-#[repr(u8)]
-pub enum ReplyType {
-    ReplyUNKNOWN,
-    ReplyNODATA,
-    ReplyNXDOMAIN,
-    ReplyCNAME,
-    ReplyIP,
-    ReplyDOMAIN,
-    ReplyRRNAME,
-    ReplySERVFAIL,
-    ReplyREFUSED,
-    ReplyNOTIMP,
-    ReplyOTHER,
-    ReplyDNSSEC,
-    ReplyNONE,
-    ReplyBLOB,
-    QueryReplyMax,
+impl Serialize for QueryType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
-#[derive(Deserialize, Serialize, Eq, PartialEq, Debug, EnumCount, EnumIter, FromPrimitive)]
-pub enum QueryType {
-    A,
-    AAAA,
-    ANY,
-    SRV,
-    SOA,
-    PTR,
-    TXT,
-    NAPTR,
-    MX,
-    DS,
-    RRSIG,
-    DNSKEY,
-    NS,
-    OTHER,
-    SVCB,
-    HTTPS,
-    MAX,
+impl<'de> Deserialize<'de> for QueryType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|name| QueryType::from(name.as_str()))
+    }
 }
@@ -0,0 +1,112 @@
+//! Gravity updates through the `gravity.sh.php` script of the web interface.
+//!
+//! The script runs `pihole -g` and streams its output as server-sent events. [`GravityUpdate`]
+//! yields the output line by line while gravity runs, and summarises the update once it finishes.
+use crate::errors::APIError;
+use crate::event_stream::EventStreamLines;
+use crate::transport::HttpStreamingResponse;
+use crate::PiHoleAPIConfigWithSession;
+
+/// Path of the gravity update script, relative to the base path
+const GRAVITY_PATH: &str = "scripts/pi-hole/php/gravity.sh.php";
+
+pub trait GravityPiHoleAPI {
+    /// Start a gravity update, like `pihole -g`, returning its progress as it runs.
+    fn update_gravity(&self) -> Result<GravityUpdate, APIError>;
+}
+
+impl GravityPiHoleAPI for PiHoleAPIConfigWithSession {
+    fn update_gravity(&self) -> Result<GravityUpdate, APIError> {
+        Ok(GravityUpdate::new(self.session_stream(GRAVITY_PATH, &[])?))
+    }
+}
+
+/// Outcome of a finished gravity update
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GravityUpdateResult {
+    /// Whether gravity finished building the blocklist
+    pub success: bool,
+    /// Number of domains on the blocklist
+    pub gravity_domains: Option<u64>,
+    /// Number of unique domains on the blocklist
+    pub unique_domains: Option<u64>,
+    /// Lines reporting failures, such as adlists which could not be downloaded
+    pub errors: Vec<String>,
+}
+
+/// Running gravity update.
+///
+/// Iterates over the output lines of gravity, without colours, as they are received.
+/// The update keeps running on the Pi Hole if this is dropped early.
+pub struct GravityUpdate {
+    lines: EventStreamLines,
+    result: GravityUpdateResult,
+    finished: bool,
+}
+
+impl GravityUpdate {
+    /// Read the output of gravity from the event stream `response`
+    pub fn new(response: HttpStreamingResponse) -> Self {
+        Self {
+            lines: EventStreamLines::new(response),
+            result: GravityUpdateResult::default(),
+            finished: false,
+        }
+    }
+
+    /// Outcome of the update, once all output has been read
+    pub fn result(&self) -> Option<&GravityUpdateResult> {
+        if self.finished {
+            Some(&self.result)
+        } else {
+            None
+        }
+    }
+
+    /// Wait for the update to finish, skipping the remaining output
+    pub fn finish(mut self) -> Result<GravityUpdateResult, APIError> {
+        for line in &mut self {
+            line?;
+        }
+        Ok(self.result)
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        if let Some(counts) = line.strip_prefix("[i] Number of gravity domains:") {
+            // e.g. `117311 (106785 unique domains)`
+            let mut numbers = counts
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|number| !number.is_empty())
+                .map(|number| number.parse().ok());
+            self.result.gravity_domains = numbers.next().flatten();
+            self.result.unique_domains = numbers.next().flatten();
+            self.result.success = self.result.gravity_domains.is_some();
+        } else if line.starts_with("[✗]") {
+            self.result.errors.push(line.to_string());
+        }
+    }
+}
+
+impl Iterator for GravityUpdate {
+    type Item = Result<String, APIError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.lines.next() {
+            Some(Ok(line)) => {
+                self.parse_line(&line);
+                Some(Ok(line))
+            }
+            Some(Err(error)) => {
+                self.finished = true;
+                Some(Err(error))
+            }
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Group management through the `groups.php` script of the web interface.
+//!
+//! Groups, clients and adlists are only managed by the web interface, so these methods need
+//! the logged in session of a [`PiHoleAPIConfigWithSession`].
+use crate::api_types::*;
+use crate::errors::APIError;
+use crate::retry::RetryPolicy;
+use crate::{take_field, PiHoleAPIConfigWithSession, PiHoleAPIHost};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+/// Path of the group management script, relative to the base path
+const GROUPS_PATH: &str = "scripts/pi-hole/php/groups.php";
+
+pub trait GroupManagementPiHoleAPI {
+    /// Get all groups.
+    fn get_groups(&self) -> Result<Vec<Group>, APIError>;
+
+    /// Add a group with `name` and an optional `description`.
+    fn add_group(
+        &self,
+        name: &str,
+        description: Option<&str>,
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Rename, describe, enable or disable the group with ID `id`.
+    fn edit_group(
+        &self,
+        id: u64,
+        name: &str,
+        description: Option<&str>,
+        enabled: bool,
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Delete the group with ID `id`.
+    fn delete_group(&self, id: u64) -> Result<ListModificationResponse, APIError>;
+
+    /// Get all configured clients.
+    fn get_clients(&self) -> Result<Vec<GroupClient>, APIError>;
+
+    /// Add a client by IP address, subnet, MAC address, hostname or interface (`:eth0`).
+    fn add_client(
+        &self,
+        ip: &str,
+        comment: Option<&str>,
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Set the comment and groups of the client with ID `id`.
+    fn edit_client(
+        &self,
+        id: u64,
+        comment: Option<&str>,
+        groups: &[u64],
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Delete the client with ID `id`.
+    fn delete_client(&self, id: u64) -> Result<ListModificationResponse, APIError>;
+
+    /// Get all adlists.
+    fn get_adlists(&self) -> Result<Vec<Adlist>, APIError>;
+
+    /// Add an adlist with the URL `address`.
+    fn add_adlist(
+        &self,
+        address: &str,
+        comment: Option<&str>,
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Set the comment, enabled state and groups of the adlist with ID `id`.
+    fn edit_adlist(
+        &self,
+        id: u64,
+        comment: Option<&str>,
+        enabled: bool,
+        groups: &[u64],
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Delete the adlist with ID `id`.
+    fn delete_adlist(&self, id: u64) -> Result<ListModificationResponse, APIError>;
+
+    /// Enable `adlist`, keeping its comment and groups.
+    fn enable_adlist(&self, adlist: &Adlist) -> Result<ListModificationResponse, APIError>;
+
+    /// Disable `adlist`, keeping its comment and groups.
+    fn disable_adlist(&self, adlist: &Adlist) -> Result<ListModificationResponse, APIError>;
+
+    /// Assign the list entry `domain` to `groups`, keeping its other settings.
+    fn set_domain_groups(
+        &self,
+        domain: &CustomListDomainDetails,
+        groups: &[u64],
+    ) -> Result<ListModificationResponse, APIError>;
+}
+
+/// Send a read-only `action` to the group management script, retried according to the retry policy
+fn groups_request<T: DeserializeOwned>(
+    api: &PiHoleAPIConfigWithSession,
+    action: &str,
+) -> Result<Vec<T>, APIError> {
+    let mut raw_data: HashMap<String, Vec<T>> =
+        api.session_json_request(GROUPS_PATH, &[("action", action)], api.get_retry_policy())?;
+    take_field(&mut raw_data, "data")
+}
+
+/// Send an `action` which modifies the Pi Hole to the group management script. These requests are never retried.
+fn groups_mutation(
+    api: &PiHoleAPIConfigWithSession,
+    action: &str,
+    params: &[(&str, &str)],
+) -> Result<ListModificationResponse, APIError> {
+    let mut form = vec![("action", action)];
+    form.extend_from_slice(params);
+    api.session_json_request(GROUPS_PATH, &form, &RetryPolicy::NONE)
+}
+
+/// Form fields assigning an entry to `groups`
+fn group_params(groups: &[String]) -> impl Iterator<Item = (&str, &str)> {
+    groups.iter().map(|group| ("groups[]", group.as_str()))
+}
+
+/// Quote `name` if needed, as `groups.php` splits the name field on spaces to add several groups at once
+fn quote_name(name: &str) -> String {
+    if name.contains(char::is_whitespace) {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    } else {
+        name.to_string()
+    }
+}
+
+/// The `id` field of delete actions, which takes a JSON array of IDs
+fn id_param(id: u64) -> String {
+    format!("[{}]", id)
+}
+
+fn status_param(enabled: bool) -> &'static str {
+    if enabled {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+impl GroupManagementPiHoleAPI for PiHoleAPIConfigWithSession {
+    fn get_groups(&self) -> Result<Vec<Group>, APIError> {
+        groups_request(self, "get_groups")
+    }
+
+    fn add_group(
+        &self,
+        name: &str,
+        description: Option<&str>,
+    ) -> Result<ListModificationResponse, APIError> {
+        groups_mutation(
+            self,
+            "add_group",
+            &[
+                ("name", &quote_name(name)),
+                ("desc", description.unwrap_or_default()),
+            ],
+        )
+    }
+
+    fn edit_group(
+        &self,
+        id: u64,
+        name: &str,
+        description: Option<&str>,
+        enabled: bool,
+    ) -> Result<ListModificationResponse, APIError> {
+        groups_mutation(
+            self,
+            "edit_group",
+            &[
+                ("id", &id.to_string()),
+                ("name", name),
+                ("desc", description.unwrap_or_default()),
+                ("status", status_param(enabled)),
+            ],
+        )
+    }
+
+    fn delete_group(&self, id: u64) -> Result<ListModificationResponse, APIError> {
+        groups_mutation(self, "delete_group", &[("id", &id_param(id))])
+    }
+
+    fn get_clients(&self) -> Result<Vec<GroupClient>, APIError> {
+        groups_request(self, "get_clients")
+    }
+
+    fn add_client(
+        &self,
+        ip: &str,
+        comment: Option<&str>,
+    ) -> Result<ListModificationResponse, APIError> {
+        groups_mutation(
+            self,
+            "add_client",
+            &[("ip", ip), ("comment", comment.unwrap_or_default())],
+        )
+    }
+
+    fn edit_client(
+        &self,
+        id: u64,
+        comment: Option<&str>,
+        groups: &[u64],
+    ) -> Result<ListModificationResponse, APIError> {
+        let id = id.to_string();
+        let groups: Vec<String> = groups.iter().map(u64::to_string).collect();
+        let mut params = vec![
+            ("id", id.as_str()),
+            ("comment", comment.unwrap_or_default()),
+        ];
+        params.extend(group_params(&groups));
+        groups_mutation(self, "edit_client", &params)
+    }
+
+    fn delete_client(&self, id: u64) -> Result<ListModificationResponse, APIError> {
+        groups_mutation(self, "delete_client", &[("id", &id_param(id))])
+    }
+
+    fn get_adlists(&self) -> Result<Vec<Adlist>, APIError> {
+        groups_request(self, "get_adlists")
+    }
+
+    fn add_adlist(
+        &self,
+        address: &str,
+        comment: Option<&str>,
+    ) -> Result<ListModificationResponse, APIError> {
+        groups_mutation(
+            self,
+            "add_adlist",
+            &[
+                ("address", address),
+                ("comment", comment.unwrap_or_default()),
+            ],
+        )
+    }
+
+    fn edit_adlist(
+        &self,
+        id: u64,
+        comment: Option<&str>,
+        enabled: bool,
+        groups: &[u64],
+    ) -> Result<ListModificationResponse, APIError> {
+        let id = id.to_string();
+        let groups: Vec<String> = groups.iter().map(u64::to_string).collect();
+        let mut params = vec![
+            ("id", id.as_str()),
+            ("comment", comment.unwrap_or_default()),
+            ("status", status_param(enabled)),
+        ];
+        params.extend(group_params(&groups));
+        groups_mutation(self, "edit_adlist", &params)
+    }
+
+    fn delete_adlist(&self, id: u64) -> Result<ListModificationResponse, APIError> {
+        groups_mutation(self, "delete_adlist", &[("id", &id_param(id))])
+    }
+
+    fn enable_adlist(&self, adlist: &Adlist) -> Result<ListModificationResponse, APIError> {
+        self.edit_adlist(adlist.id, adlist.comment.as_deref(), true, &adlist.groups)
+    }
+
+    fn disable_adlist(&self, adlist: &Adlist) -> Result<ListModificationResponse, APIError> {
+        self.edit_adlist(adlist.id, adlist.comment.as_deref(), false, &adlist.groups)
+    }
+
+    fn set_domain_groups(
+        &self,
+        domain: &CustomListDomainDetails,
+        groups: &[u64],
+    ) -> Result<ListModificationResponse, APIError> {
+        let id = domain.id.to_string();
+        let domain_type = domain.domain_type.to_string();
+        let groups: Vec<String> = groups.iter().map(u64::to_string).collect();
+        let mut params = vec![
+            ("id", id.as_str()),
+            ("type", domain_type.as_str()),
+            ("comment", domain.comment.as_str()),
+            ("status", status_param(domain.enabled)),
+        ];
+        params.extend(group_params(&groups));
+        groups_mutation(self, "edit_domain", &params)
+    }
+}
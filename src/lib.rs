@@ -3,53 +3,308 @@ use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::net::IpAddr;
 pub mod api_types;
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod auth;
+pub mod capabilities;
 mod custom_deserializers;
 pub mod errors;
+mod event_stream;
 mod fake_hash_map;
 pub mod ftl_types;
+pub mod gravity;
+pub mod groups;
+pub mod query_log;
+pub mod retry;
+pub mod search;
+mod session;
+pub mod teleporter;
+pub mod teleporter_backup;
+pub mod tls;
+pub mod transport;
+pub mod v6;
 use crate::api_types::*;
+#[cfg(feature = "async")]
+pub use crate::asynchronous::{AsyncAuthenticatedPiHoleAPI, AsyncUnauthenticatedPiHoleAPI};
+use crate::auth::{ApiKeyLocation, SecretString};
+use crate::capabilities::{Capabilities, Feature};
+use crate::query_log::QueryFilter;
+use crate::retry::RetryPolicy;
+pub use crate::session::PiHoleAPIConfigWithSession;
+#[cfg(feature = "async")]
+use crate::transport::{AsyncHttpTransport, ReqwestTransport};
+use crate::transport::{
+    HttpMethod, HttpRequest, HttpResponse, HttpTransport, TransportOptions, UreqTransport,
+};
+use chrono::NaiveDateTime;
 use std::borrow::Borrow;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 
 const NO_PARAMS: [(&str, &str); 0] = [];
 
+/// Path of the admin interface on a default Pi Hole install
+const DEFAULT_BASE_PATH: &str = "/admin/";
+
 trait PiHoleAPIHost {
     fn get_host(&self) -> &str;
+
+    fn get_base_path(&self) -> &str;
+
+    fn get_transport(&self) -> &dyn HttpTransport;
+
+    fn get_retry_policy(&self) -> &RetryPolicy;
+
+    fn get_capabilities(&self) -> Option<&Capabilities>;
+
+    #[cfg(feature = "async")]
+    fn get_async_transport(&self) -> &dyn AsyncHttpTransport;
 }
 
 trait PiHoleAPIKey {
     fn get_api_key(&self) -> &str;
+
+    fn get_api_key_location(&self) -> ApiKeyLocation;
 }
 
 /// Pi Hole API Struct
-#[derive(Debug)]
+///
+/// Cloning the config is cheap and clones share the same transport, so connections are reused
+/// across clones and threads.
+#[derive(Clone)]
 pub struct PiHoleAPIConfig {
     /// Pi Hole host
     host: String,
+
+    /// Path of the admin interface, starting and ending with `/`
+    base_path: String,
+
+    /// Transport used to send requests
+    transport: Arc<dyn HttpTransport>,
+
+    /// Transport used to send asynchronous requests
+    #[cfg(feature = "async")]
+    async_transport: Arc<dyn AsyncHttpTransport>,
+
+    /// Retry policy for read-only requests
+    retry_policy: RetryPolicy,
+
+    /// Capabilities of the host, used to refuse unsupported requests
+    capabilities: Option<Capabilities>,
 }
 
 impl PiHoleAPIConfig {
     /// Creates a new Pi Hole API instance.
     /// `host` must begin with the protocol e.g. http:// or https://
+    ///
+    /// The host is not validated, an invalid host causes every request to fail with
+    /// [`APIErrorKind::InvalidUrl`](errors::APIErrorKind::InvalidUrl). Use [`try_new`](Self::try_new) to validate it up front.
     pub fn new(host: String) -> Self {
-        Self { host }
+        Self {
+            host: host.trim_end_matches('/').to_string(),
+            base_path: DEFAULT_BASE_PATH.to_string(),
+            transport: Arc::new(UreqTransport::new()),
+            #[cfg(feature = "async")]
+            async_transport: Arc::new(ReqwestTransport::new()),
+            retry_policy: RetryPolicy::NONE,
+            capabilities: None,
+        }
+    }
+
+    /// Creates a new Pi Hole API instance, returning an error if `host` is not a valid URL.
+    /// `host` must begin with the protocol e.g. http:// or https:// and must not contain a path,
+    /// use [`with_base_path`](Self::with_base_path) for Pi Holes served under a sub-path.
+    pub fn try_new(host: String) -> Result<Self, errors::APIError> {
+        validate_host(&host)?;
+        Ok(Self::new(host))
+    }
+
+    /// Set the path of the admin interface, `/admin/` by default.
+    /// Use this for Pi Holes behind a reverse proxy, e.g. `/pihole/admin/`.
+    pub fn with_base_path(mut self, base_path: &str) -> Result<Self, errors::APIError> {
+        self.base_path = normalise_base_path(base_path)?;
+        Ok(self)
+    }
+
+    /// Replace the transports with the built-in transports configured with `options`
+    pub fn with_transport_options(
+        mut self,
+        options: &TransportOptions,
+    ) -> Result<Self, errors::APIError> {
+        self.transport = Arc::new(UreqTransport::with_options(options)?);
+        #[cfg(feature = "async")]
+        {
+            self.async_transport = Arc::new(ReqwestTransport::with_options(options)?);
+        }
+        Ok(self)
+    }
+
+    /// Retry read-only requests according to `retry_policy`.
+    /// Requests which modify the Pi Hole are never retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Send requests through `transport` instead of the default `UreqTransport`
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Refuse requests not supported by a host with `capabilities` with [`APIErrorKind::Unsupported`](errors::APIErrorKind::Unsupported)
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Probe the host for its API generation and component versions
+    pub fn detect_capabilities(&self) -> Result<Capabilities, errors::APIError> {
+        capabilities::detect(self)
+    }
+
+    /// Send asynchronous requests through `transport` instead of the default `ReqwestTransport`
+    #[cfg(feature = "async")]
+    pub fn with_async_transport(mut self, transport: Arc<dyn AsyncHttpTransport>) -> Self {
+        self.async_transport = transport;
+        self
+    }
+}
+
+impl fmt::Debug for PiHoleAPIConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PiHoleAPIConfig")
+            .field("host", &self.host)
+            .field("base_path", &self.base_path)
+            .field("retry_policy", &self.retry_policy)
+            .field("capabilities", &self.capabilities)
+            .finish_non_exhaustive()
     }
 }
 
 /// Pi Hole API Struct
-#[derive(Debug)]
+///
+/// Cloning the config is cheap and clones share the same transport, so connections are reused
+/// across clones and threads.
+#[derive(Clone)]
 pub struct PiHoleAPIConfigWithKey {
-    /// Pi Hole host
-    host: String,
+    /// Host and transport configuration
+    config: PiHoleAPIConfig,
 
     /// API key
-    api_key: String,
+    api_key: SecretString,
+
+    /// Where the API key is placed in requests
+    api_key_location: ApiKeyLocation,
 }
 
 impl PiHoleAPIConfigWithKey {
     /// Creates a new Pi Hole API instance.
     /// `host` must begin with the protocol e.g. http:// or https://
     pub fn new(host: String, api_key: String) -> Self {
-        Self { host, api_key }
+        Self::from_config(PiHoleAPIConfig::new(host), api_key)
+    }
+
+    /// Creates a new Pi Hole API instance, returning an error if `host` is not a valid URL.
+    /// See [`PiHoleAPIConfig::try_new`].
+    pub fn try_new(host: String, api_key: String) -> Result<Self, errors::APIError> {
+        Ok(Self::from_config(PiHoleAPIConfig::try_new(host)?, api_key))
+    }
+
+    /// Creates a new authenticated Pi Hole API instance from an existing config
+    pub fn from_config(config: PiHoleAPIConfig, api_key: String) -> Self {
+        Self::from_config_with_secret(config, SecretString::new(api_key))
+    }
+
+    /// Creates a new Pi Hole API instance using the API token derived from the web interface `password`
+    pub fn from_password(host: String, password: &str) -> Self {
+        Self::from_config_with_secret(
+            PiHoleAPIConfig::new(host),
+            auth::api_token_from_password(password),
+        )
+    }
+
+    /// Creates a new Pi Hole API instance using the API token stored in the `setupVars.conf` file at `path`.
+    /// This only works on the machine running the Pi Hole, usually with `/etc/pihole/setupVars.conf`.
+    pub fn from_setup_vars(host: String, path: impl AsRef<Path>) -> Result<Self, errors::APIError> {
+        Ok(Self::from_config_with_secret(
+            PiHoleAPIConfig::new(host),
+            auth::read_setup_vars_api_token(path)?,
+        ))
+    }
+
+    fn from_config_with_secret(config: PiHoleAPIConfig, api_key: SecretString) -> Self {
+        Self {
+            config,
+            api_key,
+            api_key_location: ApiKeyLocation::default(),
+        }
+    }
+
+    /// Set where the API key is placed in requests. By default it is sent in the query string.
+    pub fn with_api_key_location(mut self, api_key_location: ApiKeyLocation) -> Self {
+        self.api_key_location = api_key_location;
+        self
+    }
+
+    /// Set the path of the admin interface, `/admin/` by default.
+    /// Use this for Pi Holes behind a reverse proxy, e.g. `/pihole/admin/`.
+    pub fn with_base_path(mut self, base_path: &str) -> Result<Self, errors::APIError> {
+        self.config = self.config.with_base_path(base_path)?;
+        Ok(self)
+    }
+
+    /// Send requests through `transport` instead of the default `UreqTransport`
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.config = self.config.with_transport(transport);
+        self
+    }
+
+    /// Replace the transports with the built-in transports configured with `options`
+    pub fn with_transport_options(
+        mut self,
+        options: &TransportOptions,
+    ) -> Result<Self, errors::APIError> {
+        self.config = self.config.with_transport_options(options)?;
+        Ok(self)
+    }
+
+    /// Retry read-only requests according to `retry_policy`.
+    /// Requests which modify the Pi Hole are never retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.config = self.config.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Refuse requests not supported by a host with `capabilities` with [`APIErrorKind::Unsupported`](errors::APIErrorKind::Unsupported)
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.config = self.config.with_capabilities(capabilities);
+        self
+    }
+
+    /// Probe the host for its API generation and component versions
+    pub fn detect_capabilities(&self) -> Result<Capabilities, errors::APIError> {
+        self.config.detect_capabilities()
+    }
+
+    /// Send asynchronous requests through `transport` instead of the default `ReqwestTransport`
+    #[cfg(feature = "async")]
+    pub fn with_async_transport(mut self, transport: Arc<dyn AsyncHttpTransport>) -> Self {
+        self.config = self.config.with_async_transport(transport);
+        self
+    }
+}
+
+impl fmt::Debug for PiHoleAPIConfigWithKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PiHoleAPIConfigWithKey")
+            .field("host", &self.config.host)
+            .field("base_path", &self.config.base_path)
+            .field("api_key", &self.api_key)
+            .field("api_key_location", &self.api_key_location)
+            .finish_non_exhaustive()
     }
 }
 
@@ -57,18 +312,289 @@ impl PiHoleAPIHost for PiHoleAPIConfig {
     fn get_host(&self) -> &str {
         &self.host
     }
+
+    fn get_base_path(&self) -> &str {
+        &self.base_path
+    }
+
+    fn get_transport(&self) -> &dyn HttpTransport {
+        self.transport.as_ref()
+    }
+
+    fn get_retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    fn get_capabilities(&self) -> Option<&Capabilities> {
+        self.capabilities.as_ref()
+    }
+
+    #[cfg(feature = "async")]
+    fn get_async_transport(&self) -> &dyn AsyncHttpTransport {
+        self.async_transport.as_ref()
+    }
 }
 
 impl PiHoleAPIHost for PiHoleAPIConfigWithKey {
     fn get_host(&self) -> &str {
-        &self.host
+        self.config.get_host()
+    }
+
+    fn get_base_path(&self) -> &str {
+        self.config.get_base_path()
+    }
+
+    fn get_transport(&self) -> &dyn HttpTransport {
+        self.config.get_transport()
+    }
+
+    fn get_retry_policy(&self) -> &RetryPolicy {
+        self.config.get_retry_policy()
+    }
+
+    fn get_capabilities(&self) -> Option<&Capabilities> {
+        self.config.get_capabilities()
+    }
+
+    #[cfg(feature = "async")]
+    fn get_async_transport(&self) -> &dyn AsyncHttpTransport {
+        self.config.get_async_transport()
     }
 }
 
 impl PiHoleAPIKey for PiHoleAPIConfigWithKey {
     fn get_api_key(&self) -> &str {
-        &self.api_key
+        self.api_key.expose_secret()
+    }
+
+    fn get_api_key_location(&self) -> ApiKeyLocation {
+        self.api_key_location
+    }
+}
+
+/// Check that `host` is an HTTP or HTTPS URL without a path, query or fragment
+fn validate_host(host: &str) -> Result<(), errors::APIError> {
+    let invalid_host =
+        |reason: &str| errors::APIErrorKind::InvalidUrl(format!("{}: {}", host, reason));
+    let url = url::Url::parse(host).map_err(|error| invalid_host(&error.to_string()))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(invalid_host("scheme must be http or https").into());
     }
+    if url.host().is_none() {
+        return Err(invalid_host("missing host").into());
+    }
+    if url.path() != "/" || url.query().is_some() || url.fragment().is_some() {
+        return Err(invalid_host(
+            "must not contain a path, query or fragment, use with_base_path to set the path",
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Path of the Pi-hole v6 API, which is served next to the admin interface at `base_path`,
+/// e.g. `/pihole/api` for `/pihole/admin/`
+fn v6_api_path(base_path: &str) -> String {
+    let prefix = base_path
+        .strip_suffix("admin/")
+        .filter(|prefix| prefix.ends_with('/'))
+        .unwrap_or(base_path);
+    format!("{}api", prefix)
+}
+
+/// Add leading and trailing slashes to `base_path` and check it only contains a path
+fn normalise_base_path(base_path: &str) -> Result<String, errors::APIError> {
+    if base_path.contains(['?', '#']) {
+        return Err(errors::APIErrorKind::InvalidUrl(format!(
+            "{}: base path must not contain a query or fragment",
+            base_path
+        ))
+        .into());
+    }
+    let trimmed = base_path.trim_matches('/');
+    if trimmed.is_empty() {
+        Ok("/".to_string())
+    } else {
+        Ok(format!("/{}/", trimmed))
+    }
+}
+
+/// Return [`errors::APIErrorKind::Unsupported`] with the context of `request`
+/// if the capabilities of `api` are known and do not include `feature`
+fn require_feature(
+    api: &impl PiHoleAPIHost,
+    feature: Feature,
+    request: &HttpRequest,
+) -> Result<(), errors::APIError> {
+    match api.get_capabilities() {
+        Some(capabilities) => capabilities
+            .require(feature)
+            .map_err(|error| error.with_context(request, None)),
+        None => Ok(()),
+    }
+}
+
+/// Build a `method` request for `path_query` under the base path of `api`, appending `params` to the query
+fn api_request<I, K, V>(
+    api: &impl PiHoleAPIHost,
+    method: HttpMethod,
+    path_query: &str,
+    params: I,
+) -> Result<HttpRequest, errors::APIError>
+where
+    I: IntoIterator,
+    K: AsRef<str>,
+    V: AsRef<str>,
+    <I as IntoIterator>::Item: Borrow<(K, V)>,
+{
+    let url = format!("{}{}{}", api.get_host(), api.get_base_path(), path_query);
+    let mut url = url::Url::parse_with_params(&url, params).map_err(|error| {
+        errors::APIError::from(errors::APIErrorKind::InvalidUrl(format!(
+            "{}: {}",
+            url, error
+        )))
+        .with_url_context(method, &url)
+    })?;
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+    Ok(HttpRequest::new(method, url))
+}
+
+/// Send `request` and return the body text, treating error status codes as errors.
+/// Failed attempts are retried according to `retry_policy`.
+fn send_request(
+    transport: &dyn HttpTransport,
+    request: HttpRequest,
+    retry_policy: &RetryPolicy,
+) -> Result<String, errors::APIError> {
+    send_request_for_response(transport, request, retry_policy)?.text()
+}
+
+/// Send `request` and return the response, treating error status codes as errors.
+/// Failed attempts are retried according to `retry_policy`.
+/// Errors carry the endpoint and parameters of the request.
+fn send_request_for_response(
+    transport: &dyn HttpTransport,
+    request: HttpRequest,
+    retry_policy: &RetryPolicy,
+) -> Result<HttpResponse, errors::APIError> {
+    let mut retry = 0;
+    loop {
+        let result = transport.send(request.clone()).and_then(|response| {
+            if response.status >= 400 {
+                return Err(errors::APIErrorKind::HttpStatus(response.status).into());
+            }
+            Ok(response)
+        });
+        match result {
+            Err(error) if retry_policy.should_retry(retry, &error) => {
+                std::thread::sleep(retry_policy.backoff_with_jitter(retry));
+                retry += 1;
+            }
+            result => return result.map_err(|error| error.with_context(&request, None)),
+        }
+    }
+}
+
+/// Query parameters of an `api_db.php` request for `action` between `from` and `until`
+fn time_range_params(
+    action: &'static str,
+    from: NaiveDateTime,
+    until: NaiveDateTime,
+) -> Vec<(&'static str, String)> {
+    vec![
+        (action, String::new()),
+        ("from", from.and_utc().timestamp().to_string()),
+        ("until", until.and_utc().timestamp().to_string()),
+    ]
+}
+
+/// Convert a Unix timestamp in seconds from a response
+fn timestamp_to_naive_datetime(timestamp: f64) -> Result<NaiveDateTime, errors::APIError> {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|datetime| datetime.naive_utc())
+        .ok_or_else(|| {
+            errors::APIErrorKind::InvalidResponse(format!("Invalid timestamp {}", timestamp)).into()
+        })
+}
+
+/// Split the plain text `recentBlocked` response into domains
+fn parse_recent_blocked(response_text: &str) -> Vec<String> {
+    response_text
+        .lines()
+        .map(str::trim)
+        .filter(|domain| !domain.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Remove `field` from a response object, returning [`errors::APIErrorKind::MissingField`] if it is absent
+fn take_field<T>(raw_data: &mut HashMap<String, T>, field: &str) -> Result<T, errors::APIError> {
+    raw_data
+        .remove(field)
+        .ok_or_else(|| errors::APIErrorKind::MissingField(field.to_string()).into())
+}
+
+/// Convert a `[domain, ip]` entry of the custom DNS list
+fn parse_custom_dns_record(record: Vec<String>) -> Result<CustomDNSRecord, errors::APIError> {
+    match record.as_slice() {
+        [domain, ip_address] => Ok(CustomDNSRecord {
+            domain: domain.clone(),
+            ip_address: ip_address.parse().map_err(|_| {
+                errors::APIErrorKind::InvalidResponse(format!(
+                    "Invalid IP address in custom DNS record: {}",
+                    ip_address
+                ))
+            })?,
+        }),
+        _ => Err(errors::APIErrorKind::InvalidResponse(format!(
+            "Invalid custom DNS record: {:?}",
+            record
+        ))
+        .into()),
+    }
+}
+
+/// Convert a `[domain, target]` entry of the custom CNAME list
+fn parse_custom_cname_record(record: Vec<String>) -> Result<CustomCNAMERecord, errors::APIError> {
+    match record.as_slice() {
+        [domain, target_domain] => Ok(CustomCNAMERecord {
+            domain: domain.clone(),
+            target_domain: target_domain.clone(),
+        }),
+        _ => Err(errors::APIErrorKind::InvalidResponse(format!(
+            "Invalid custom CNAME record: {:?}",
+            record
+        ))
+        .into()),
+    }
+}
+
+/// Check `response` to `request` for error messages and return the body text.
+/// Errors carry the endpoint and parameters of the request.
+fn parse_text_response(
+    request: &HttpRequest,
+    response: &HttpResponse,
+) -> Result<String, errors::APIError> {
+    response
+        .text()
+        .and_then(|response_text| {
+            errors::detect_response_errors(&response_text)?;
+            Ok(response_text)
+        })
+        .map_err(|error| error.with_context(request, Some(response.status)))
+}
+
+/// Check `response` to `request` for error messages and deserialize it.
+/// Errors carry the endpoint and parameters of the request.
+fn parse_json_response<T: DeserializeOwned>(
+    request: &HttpRequest,
+    response: &HttpResponse,
+) -> Result<T, errors::APIError> {
+    let response_text = parse_text_response(request, response)?;
+    serde_json::from_str(&response_text)
+        .map_err(|error| errors::APIError::from(error).with_context(request, Some(response.status)))
 }
 
 pub trait UnauthenticatedPiHoleAPI {
@@ -88,8 +614,10 @@ pub trait UnauthenticatedPiHoleAPI {
     fn get_versions(&self) -> Result<Versions, errors::APIError>;
 }
 
+/// Send an unauthenticated request to the PHP API, which also needs `feature`
 fn simple_json_request<T, I, K, V>(
-    host: &str,
+    api: &impl PiHoleAPIHost,
+    feature: Feature,
     path_query: &str,
     params: I,
 ) -> Result<T, errors::APIError>
@@ -100,15 +628,12 @@ where
     V: AsRef<str>,
     <I as IntoIterator>::Item: Borrow<(K, V)>,
 {
-    let path = format!("{}{}", host, path_query);
-    let response = ureq::get(
-        url::Url::parse_with_params(&path, params)
-            .expect("Invalid URL")
-            .as_str(),
-    )
-    .call()?
-    .into_json()?;
-    Ok(response)
+    let request = api_request(api, HttpMethod::Get, path_query, params)?;
+    require_feature(api, Feature::PhpApi, &request)?;
+    require_feature(api, feature, &request)?;
+    let response =
+        send_request_for_response(api.get_transport(), request.clone(), api.get_retry_policy())?;
+    parse_json_response(&request, &response)
 }
 
 impl<T> UnauthenticatedPiHoleAPI for T
@@ -116,17 +641,18 @@ where
     T: PiHoleAPIHost,
 {
     fn get_summary_raw(&self) -> Result<SummaryRaw, errors::APIError> {
-        simple_json_request(self.get_host(), "/admin/api.php?summaryRaw", &NO_PARAMS)
+        simple_json_request(self, Feature::PhpApi, "api.php?summaryRaw", &NO_PARAMS)
     }
 
     fn get_summary(&self) -> Result<Summary, errors::APIError> {
-        simple_json_request(self.get_host(), "/admin/api.php?summary", &NO_PARAMS)
+        simple_json_request(self, Feature::PhpApi, "api.php?summary", &NO_PARAMS)
     }
 
     fn get_over_time_data_10_mins(&self) -> Result<OverTimeData, errors::APIError> {
         simple_json_request(
-            self.get_host(),
-            "/admin/api.php?overTimeData10mins",
+            self,
+            Feature::PhpApi,
+            "api.php?overTimeData10mins",
             &NO_PARAMS,
         )
     }
@@ -134,13 +660,13 @@ where
     /// Get simple PiHole version
     fn get_version(&self) -> Result<u32, errors::APIError> {
         let raw_version: Version =
-            simple_json_request(self.get_host(), "/admin/api.php?version", &NO_PARAMS)?;
+            simple_json_request(self, Feature::PhpApi, "api.php?version", &NO_PARAMS)?;
         Ok(raw_version.version)
     }
 
     /// Get versions of core, FTL and web and if updates are available
     fn get_versions(&self) -> Result<Versions, errors::APIError> {
-        simple_json_request(self.get_host(), "/admin/api.php?versions", &NO_PARAMS)
+        simple_json_request(self, Feature::Versions, "api.php?versions", &NO_PARAMS)
     }
 }
 
@@ -169,6 +695,9 @@ pub trait AuthenticatedPiHoleAPI {
     /// Get all DNS query data. Limit the number of items with `count`.
     fn get_all_queries(&self, count: u32) -> Result<Vec<Query>, errors::APIError>;
 
+    /// Get the DNS queries matching `filter`.
+    fn get_queries(&self, filter: &QueryFilter) -> Result<Vec<Query>, errors::APIError>;
+
     /// Enable the Pi-Hole.
     fn enable(&self) -> Result<Status, errors::APIError>;
 
@@ -185,33 +714,85 @@ pub trait AuthenticatedPiHoleAPI {
     /// Order of clients in the Vector is the same as for get_client_names
     fn get_over_time_data_clients(&self) -> Result<HashMap<String, Vec<u64>>, errors::APIError>;
 
+    /// Get the percentage of queries answered by each forward destination for each 10 minute period.
+    fn get_over_time_data_forwards(&self) -> Result<OverTimeDataForwards, errors::APIError>;
+
+    /// Get the percentage of A and AAAA queries for each 10 minute period.
+    fn get_over_time_data_query_types(&self) -> Result<OverTimeDataQueryTypes, errors::APIError>;
+
+    /// Get the forward destinations in the order used by `get_over_time_data_forwards`.
+    fn get_forward_destination_names(&self) -> Result<ForwardDestinationNames, errors::APIError>;
+
+    /// Get the most recently blocked domains, newest last. Limit the number of items with `count`.
+    fn get_recent_blocked(&self, count: Option<u32>) -> Result<Vec<String>, errors::APIError>;
+
     /// Get information about network clients.
     fn get_network(&self) -> Result<Network, errors::APIError>;
 
     /// Get the total number of queries received.
     fn get_queries_count(&self) -> Result<u64, errors::APIError>;
 
+    /// Get the queries stored in the long-term database between `from` and `until` (UTC).
+    fn get_database_queries(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<Vec<DatabaseQuery>, errors::APIError>;
+
+    /// Get the top clients between `from` and `until` (UTC) from the long-term database.
+    fn get_database_top_clients(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<TopClients, errors::APIError>;
+
+    /// Get the top domains and their number of queries between `from` and `until` (UTC) from the long-term database.
+    fn get_database_top_domains(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<HashMap<String, u64>, errors::APIError>;
+
+    /// Get the top blocked domains and their number of queries between `from` and `until` (UTC) from the long-term database.
+    fn get_database_top_ads(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<HashMap<String, u64>, errors::APIError>;
+
+    /// Get the number of domains and ads for each `interval` between `from` and `until` (UTC) from the long-term database.
+    /// The Pi Hole uses 10 minute intervals if `interval` is 10 seconds or less.
+    fn get_database_graph_data(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+        interval: Duration,
+    ) -> Result<OverTimeData, errors::APIError>;
+
+    /// Get the size of the long-term database in bytes.
+    fn get_database_file_size(&self) -> Result<u64, errors::APIError>;
+
+    /// Get the time of the oldest query in the long-term database, or `None` if it is empty.
+    fn get_database_min_timestamp(&self) -> Result<Option<NaiveDateTime>, errors::APIError>;
+
     /// Add domains to a custom white/blacklist.
-    /// Acceptable lists are: `white`, `black`, `white_regex`, `black_regex`, `white_wild`, `black_wild`, `audit`.
     fn list_add(
         &self,
         domain: &str,
-        list: &str,
+        list: DomainList,
     ) -> Result<ListModificationResponse, errors::APIError>;
 
     /// Remove domain to a custom white/blacklist.
-    /// Acceptable lists are: `white`, `black`, `white_regex`, `black_regex`, `white_wild`, `black_wild`, `audit`.
     fn list_remove(
         &self,
         domain: &str,
-        list: &str,
+        list: DomainList,
     ) -> Result<ListModificationResponse, errors::APIError>;
 
     /// Get a list of domains on a particular custom white/blacklist
-    /// Acceptable lists are: `white`, `black`, `white_regex`, `black_regex`, `white_wild`, `black_wild`, `audit`.
     fn list_get_domains(
         &self,
-        list: &str,
+        list: DomainList,
     ) -> Result<Vec<CustomListDomainDetails>, errors::APIError>;
 
     /// Get a list of custom DNS records
@@ -252,33 +833,128 @@ pub trait AuthenticatedPiHoleAPI {
     fn get_max_logage(&self) -> Result<f32, errors::APIError>;
 }
 
-fn authenticated_json_request<'a, T, I, K, V>(
-    host: &str,
+/// Build an authenticated request, placing the API key according to the API key location of `api`
+fn authenticated_request<I, K, V>(
+    api: &(impl PiHoleAPIHost + PiHoleAPIKey),
     path_query: &str,
     params: I,
-    api_key: &'a str,
-) -> Result<T, errors::APIError>
+) -> Result<HttpRequest, errors::APIError>
 where
-    T: DeserializeOwned,
     I: IntoIterator<Item = (K, V)>,
     K: AsRef<str>,
     V: AsRef<str>,
-    // <I as IntoIterator>::Item: Borrow<(K, V)>,
 {
-    let path = format!("{}{}", host, path_query);
-    let auth_params = [("auth".to_string(), api_key.to_string())];
     let converted_params: Vec<(String, String)> = params
         .into_iter()
         .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
         .collect();
-    let url = url::Url::parse_with_params(&path, converted_params.iter().chain(auth_params.iter()))
-        .expect("Invalid URL");
-    let response_text = ureq::get(url.as_str()).call()?.into_string()?;
-    errors::detect_response_errors(&response_text)?;
-    match serde_json::from_str::<T>(&response_text) {
-        Ok(response) => Ok(response),
-        Err(error) => Err(error.into()),
+    let request = match api.get_api_key_location() {
+        ApiKeyLocation::QueryString => {
+            let auth_params = [("auth".to_string(), api.get_api_key().to_string())];
+            api_request(
+                api,
+                HttpMethod::Get,
+                path_query,
+                converted_params.iter().chain(auth_params.iter()),
+            )?
+        }
+        ApiKeyLocation::PostBody => {
+            let mut request = api_request(api, HttpMethod::Post, path_query, &converted_params)?;
+            let body = url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("auth", api.get_api_key())
+                .finish();
+            request.body = Some(body.into_bytes());
+            request.with_header("Content-Type", "application/x-www-form-urlencoded")
+        }
+    };
+    if api.get_api_key().is_empty() {
+        return Err(errors::APIError::from(errors::APIErrorKind::MissingAPIKey)
+            .with_context(&request, None));
     }
+    Ok(request)
+}
+
+/// Send an authenticated read-only request, retried according to the retry policy of `api`
+fn authenticated_json_request<T, I, K, V>(
+    api: &(impl PiHoleAPIHost + PiHoleAPIKey),
+    path_query: &str,
+    params: I,
+) -> Result<T, errors::APIError>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    authenticated_json_request_with_retries(
+        api,
+        Feature::PhpApi,
+        path_query,
+        params,
+        api.get_retry_policy(),
+    )
+}
+
+/// Send an authenticated read-only request for a plain text response,
+/// retried according to the retry policy of `api`
+fn authenticated_text_request<I, K, V>(
+    api: &(impl PiHoleAPIHost + PiHoleAPIKey),
+    path_query: &str,
+    params: I,
+) -> Result<String, errors::APIError>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let request = authenticated_request(api, path_query, params)?;
+    require_feature(api, Feature::PhpApi, &request)?;
+    let response =
+        send_request_for_response(api.get_transport(), request.clone(), api.get_retry_policy())?;
+    parse_text_response(&request, &response)
+}
+
+/// Send an authenticated request which modifies the Pi Hole. These requests are never retried.
+fn authenticated_json_mutation<T, I, K, V>(
+    api: &(impl PiHoleAPIHost + PiHoleAPIKey),
+    path_query: &str,
+    params: I,
+) -> Result<T, errors::APIError>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    authenticated_json_request_with_retries(
+        api,
+        Feature::PhpApi,
+        path_query,
+        params,
+        &RetryPolicy::NONE,
+    )
+}
+
+/// Send an authenticated request to the PHP API, which also needs `feature`,
+/// retried according to `retry_policy`
+fn authenticated_json_request_with_retries<T, I, K, V>(
+    api: &(impl PiHoleAPIHost + PiHoleAPIKey),
+    feature: Feature,
+    path_query: &str,
+    params: I,
+    retry_policy: &RetryPolicy,
+) -> Result<T, errors::APIError>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let request = authenticated_request(api, path_query, params)?;
+    require_feature(api, Feature::PhpApi, &request)?;
+    require_feature(api, feature, &request)?;
+    let response = send_request_for_response(api.get_transport(), request.clone(), retry_policy)?;
+    parse_json_response(&request, &response)
 }
 
 impl<T> AuthenticatedPiHoleAPI for T
@@ -287,19 +963,17 @@ where
 {
     fn get_top_items(&self, count: &Option<u32>) -> Result<TopItems, errors::APIError> {
         authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
+            self,
+            "api.php",
             [("topItems", count.unwrap_or(10).to_string())],
-            self.get_api_key(),
         )
     }
 
     fn get_top_clients(&self, count: &Option<u32>) -> Result<TopClients, errors::APIError> {
         authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php?",
+            self,
+            "api.php?",
             [("topClients", count.unwrap_or(10).to_string())],
-            self.get_api_key(),
         )
     }
 
@@ -308,10 +982,9 @@ where
         count: Option<u32>,
     ) -> Result<TopClientsBlocked, errors::APIError> {
         authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php?",
+            self,
+            "api.php?",
             [("topClientsBlocked", count.unwrap_or(10).to_string())],
-            self.get_api_key(),
         )
     }
 
@@ -320,165 +993,207 @@ where
         unsorted: bool,
     ) -> Result<ForwardDestinations, errors::APIError> {
         let param_value = if unsorted { "unsorted" } else { "" };
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("getForwardDestinations", param_value)],
-            self.get_api_key(),
-        )
+        authenticated_json_request(self, "api.php", [("getForwardDestinations", param_value)])
     }
 
     fn get_query_types(&self) -> Result<QueryTypes, errors::APIError> {
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("getQueryTypes", "")],
-            self.get_api_key(),
-        )
+        authenticated_json_request(self, "api.php", [("getQueryTypes", "")])
     }
 
     fn get_all_queries(&self, count: u32) -> Result<Vec<Query>, errors::APIError> {
-        let mut raw_data: HashMap<String, Vec<Query>> = authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
+        let mut raw_data: HashMap<String, Vec<Query>> = authenticated_json_request_with_retries(
+            self,
+            Feature::ExtendedQueryLog,
+            "api.php",
             [("getAllQueries", count.to_string())],
-            self.get_api_key(),
+            self.get_retry_policy(),
+        )?;
+        take_field(&mut raw_data, "data")
+    }
+
+    fn get_queries(&self, filter: &QueryFilter) -> Result<Vec<Query>, errors::APIError> {
+        let mut raw_data: HashMap<String, Vec<Query>> = authenticated_json_request_with_retries(
+            self,
+            Feature::ExtendedQueryLog,
+            "api.php",
+            filter.params(),
+            self.get_retry_policy(),
         )?;
-        Ok(raw_data.remove("data").unwrap())
+        Ok(filter.apply(take_field(&mut raw_data, "data")?))
     }
 
     fn enable(&self) -> Result<Status, errors::APIError> {
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php?",
-            [("enable", "")],
-            self.get_api_key(),
-        )
+        authenticated_json_mutation(self, "api.php?", [("enable", "")])
     }
 
     fn disable(&self, seconds: u64) -> Result<Status, errors::APIError> {
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("disable", seconds.to_string())],
-            self.get_api_key(),
-        )
+        authenticated_json_mutation(self, "api.php", [("disable", seconds.to_string())])
     }
 
     fn get_cache_info(&self) -> Result<CacheInfo, errors::APIError> {
-        let mut raw_data: HashMap<String, CacheInfo> = authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("getCacheInfo", "")],
-            self.get_api_key(),
-        )?;
-        Ok(raw_data.remove("cacheinfo").expect("Missing cache info"))
+        let mut raw_data: HashMap<String, CacheInfo> =
+            authenticated_json_request(self, "api.php", [("getCacheInfo", "")])?;
+        take_field(&mut raw_data, "cacheinfo")
     }
 
     fn get_client_names(&self) -> Result<Vec<ClientName>, errors::APIError> {
-        let mut raw_data: HashMap<String, Vec<ClientName>> = authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("getClientNames", "")],
-            self.get_api_key(),
-        )?;
-        Ok(raw_data
-            .remove("clients")
-            .expect("Missing clients attribute"))
+        let mut raw_data: HashMap<String, Vec<ClientName>> =
+            authenticated_json_request(self, "api.php", [("getClientNames", "")])?;
+        take_field(&mut raw_data, "clients")
     }
 
     fn get_over_time_data_clients(&self) -> Result<HashMap<String, Vec<u64>>, errors::APIError> {
         let mut raw_data: HashMap<String, FakeHashMap<String, Vec<u64>>> =
-            authenticated_json_request(
-                self.get_host(),
-                "/admin/api.php",
-                [("overTimeDataClients", "")],
-                self.get_api_key(),
-            )?;
+            authenticated_json_request(self, "api.php", [("overTimeDataClients", "")])?;
+
+        Ok(take_field(&mut raw_data, "over_time")?.into())
+    }
+
+    fn get_over_time_data_forwards(&self) -> Result<OverTimeDataForwards, errors::APIError> {
+        authenticated_json_request(self, "api.php", [("overTimeDataForwards", "")])
+    }
+
+    fn get_over_time_data_query_types(&self) -> Result<OverTimeDataQueryTypes, errors::APIError> {
+        authenticated_json_request(self, "api.php", [("overTimeDataQueryTypes", "")])
+    }
+
+    fn get_forward_destination_names(&self) -> Result<ForwardDestinationNames, errors::APIError> {
+        authenticated_json_request(self, "api.php", [("getForwardDestinationNames", "")])
+    }
 
-        Ok(raw_data
-            .remove("over_time")
-            .expect("Missing over_time attribute")
-            .into())
+    fn get_recent_blocked(&self, count: Option<u32>) -> Result<Vec<String>, errors::APIError> {
+        let count = count.map(|count| count.to_string()).unwrap_or_default();
+        let response_text =
+            authenticated_text_request(self, "api.php", [("recentBlocked", count)])?;
+        Ok(parse_recent_blocked(&response_text))
     }
 
     fn get_network(&self) -> Result<Network, errors::APIError> {
+        authenticated_json_request(self, "api_db.php", [("network", "")])
+    }
+
+    fn get_queries_count(&self) -> Result<u64, errors::APIError> {
+        let mut raw_data: HashMap<String, u64> =
+            authenticated_json_request(self, "api_db.php", [("getQueriesCount", "")])?;
+        take_field(&mut raw_data, "count")
+    }
+
+    fn get_database_queries(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<Vec<DatabaseQuery>, errors::APIError> {
+        let mut raw_data: HashMap<String, Vec<DatabaseQuery>> = authenticated_json_request(
+            self,
+            "api_db.php",
+            time_range_params("getAllQueries", from, until),
+        )?;
+        take_field(&mut raw_data, "data")
+    }
+
+    fn get_database_top_clients(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<TopClients, errors::APIError> {
         authenticated_json_request(
-            self.get_host(),
-            "/admin/api_db.php",
-            [("network", "")],
-            self.get_api_key(),
+            self,
+            "api_db.php",
+            time_range_params("topClients", from, until),
         )
     }
 
-    fn get_queries_count(&self) -> Result<u64, errors::APIError> {
-        let raw_data: HashMap<String, u64> = authenticated_json_request(
-            self.get_host(),
-            "/admin/api_db.php",
-            [("getQueriesCount", "")],
-            self.get_api_key(),
+    fn get_database_top_domains(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<HashMap<String, u64>, errors::APIError> {
+        let mut raw_data: HashMap<String, FakeHashMap<String, u64>> = authenticated_json_request(
+            self,
+            "api_db.php",
+            time_range_params("topDomains", from, until),
+        )?;
+        Ok(take_field(&mut raw_data, "top_domains")?.into())
+    }
+
+    fn get_database_top_ads(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<HashMap<String, u64>, errors::APIError> {
+        let mut raw_data: HashMap<String, FakeHashMap<String, u64>> = authenticated_json_request(
+            self,
+            "api_db.php",
+            time_range_params("topAds", from, until),
         )?;
-        Ok(*raw_data.get("count").expect("Missing count attribute"))
+        Ok(take_field(&mut raw_data, "top_ads")?.into())
+    }
+
+    fn get_database_graph_data(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+        interval: Duration,
+    ) -> Result<OverTimeData, errors::APIError> {
+        let mut params = time_range_params("getGraphData", from, until);
+        params.push(("interval", interval.as_secs().to_string()));
+        authenticated_json_request(self, "api_db.php", params)
+    }
+
+    fn get_database_file_size(&self) -> Result<u64, errors::APIError> {
+        let mut raw_data: HashMap<String, u64> =
+            authenticated_json_request(self, "api_db.php", [("getDBfilesize", "")])?;
+        take_field(&mut raw_data, "filesize")
+    }
+
+    fn get_database_min_timestamp(&self) -> Result<Option<NaiveDateTime>, errors::APIError> {
+        let mut raw_data: HashMap<String, Option<f64>> =
+            authenticated_json_request(self, "api_db.php", [("getMinTimestamp", "")])?;
+        take_field(&mut raw_data, "mintimestamp")?
+            .map(timestamp_to_naive_datetime)
+            .transpose()
     }
 
     fn list_add(
         &self,
         domain: &str,
-        list: &str,
+        list: DomainList,
     ) -> Result<ListModificationResponse, errors::APIError> {
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("add", domain), ("list", list)],
-            self.get_api_key(),
-        )
+        authenticated_json_mutation(self, "api.php", [("add", domain), ("list", list.as_str())])
     }
 
     fn list_remove(
         &self,
         domain: &str,
-        list: &str,
+        list: DomainList,
     ) -> Result<ListModificationResponse, errors::APIError> {
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("sub", domain), ("list", list)],
-            self.get_api_key(),
-        )
+        authenticated_json_mutation(self, "api.php", [("sub", domain), ("list", list.as_str())])
     }
 
     fn list_get_domains(
         &self,
-        list: &str,
+        list: DomainList,
     ) -> Result<Vec<CustomListDomainDetails>, errors::APIError> {
         // if not "add" or "sub", api.php defaults to the "get_domains" action
         let mut raw_data: HashMap<String, Vec<CustomListDomainDetails>> =
-            authenticated_json_request(
-                self.get_host(),
-                "/admin/api.php",
-                [("get", ""), ("list", list)],
-                self.get_api_key(),
-            )?;
-        Ok(raw_data.remove("data").unwrap())
+            authenticated_json_request(self, "api.php", [("get", ""), ("list", list.as_str())])?;
+        take_field(&mut raw_data, "data")
     }
 
     fn get_custom_dns_records(&self) -> Result<Vec<CustomDNSRecord>, errors::APIError> {
-        let mut raw_data: HashMap<String, Vec<Vec<String>>> = authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("customdns", ""), ("action", "get")],
-            self.get_api_key(),
-        )?;
+        let mut raw_data: HashMap<String, Vec<Vec<String>>> =
+            authenticated_json_request_with_retries(
+                self,
+                Feature::CustomDns,
+                "api.php",
+                [("customdns", ""), ("action", "get")],
+                self.get_retry_policy(),
+            )?;
 
-        Ok(raw_data
-            .remove("data")
-            .unwrap()
+        take_field(&mut raw_data, "data")?
             .into_iter()
-            .map(|list_record| CustomDNSRecord {
-                domain: list_record[0].clone(),
-                ip_address: list_record[1].parse().unwrap(),
-            })
-            .collect())
+            .map(parse_custom_dns_record)
+            .collect()
     }
 
     fn add_custom_dns_record(
@@ -486,16 +1201,17 @@ where
         ip: &IpAddr,
         domain: &str,
     ) -> Result<ListModificationResponse, errors::APIError> {
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
+        authenticated_json_request_with_retries(
+            self,
+            Feature::CustomDns,
+            "api.php",
             [
                 ("customdns", ""),
                 ("action", "add"),
                 ("ip", &ip.to_string()),
                 ("domain", domain),
             ],
-            self.get_api_key(),
+            &RetryPolicy::NONE,
         )
     }
 
@@ -504,36 +1220,34 @@ where
         ip: &IpAddr,
         domain: &str,
     ) -> Result<ListModificationResponse, errors::APIError> {
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
+        authenticated_json_request_with_retries(
+            self,
+            Feature::CustomDns,
+            "api.php",
             [
                 ("customdns", ""),
                 ("action", "delete"),
                 ("ip", &ip.to_string()),
                 ("domain", domain),
             ],
-            self.get_api_key(),
+            &RetryPolicy::NONE,
         )
     }
 
     fn get_custom_cname_records(&self) -> Result<Vec<CustomCNAMERecord>, errors::APIError> {
-        let mut raw_data: HashMap<String, Vec<Vec<String>>> = authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("customcname", ""), ("action", "get")],
-            self.get_api_key(),
-        )?;
+        let mut raw_data: HashMap<String, Vec<Vec<String>>> =
+            authenticated_json_request_with_retries(
+                self,
+                Feature::CustomCname,
+                "api.php",
+                [("customcname", ""), ("action", "get")],
+                self.get_retry_policy(),
+            )?;
 
-        Ok(raw_data
-            .remove("data")
-            .unwrap()
+        take_field(&mut raw_data, "data")?
             .into_iter()
-            .map(|list_record| CustomCNAMERecord {
-                domain: list_record[0].clone(),
-                target_domain: list_record[1].clone(),
-            })
-            .collect())
+            .map(parse_custom_cname_record)
+            .collect()
     }
 
     fn add_custom_cname_record(
@@ -541,16 +1255,17 @@ where
         domain: &str,
         target_domain: &str,
     ) -> Result<ListModificationResponse, errors::APIError> {
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
+        authenticated_json_request_with_retries(
+            self,
+            Feature::CustomCname,
+            "api.php",
             [
                 ("customcname", ""),
                 ("action", "add"),
                 ("domain", domain),
                 ("target", target_domain),
             ],
-            self.get_api_key(),
+            &RetryPolicy::NONE,
         )
     }
 
@@ -559,26 +1274,23 @@ where
         domain: &str,
         target_domain: &str,
     ) -> Result<ListModificationResponse, errors::APIError> {
-        authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
+        authenticated_json_request_with_retries(
+            self,
+            Feature::CustomCname,
+            "api.php",
             [
                 ("customcname", ""),
                 ("action", "delete"),
                 ("domain", domain),
                 ("target", target_domain),
             ],
-            self.get_api_key(),
+            &RetryPolicy::NONE,
         )
     }
 
     fn get_max_logage(&self) -> Result<f32, errors::APIError> {
-        let mut raw_data: HashMap<String, f32> = authenticated_json_request(
-            self.get_host(),
-            "/admin/api.php",
-            [("getMaxlogage", "")],
-            self.get_api_key(),
-        )?;
-        Ok(raw_data.remove("maxlogage").unwrap())
+        let mut raw_data: HashMap<String, f32> =
+            authenticated_json_request(self, "api.php", [("getMaxlogage", "")])?;
+        take_field(&mut raw_data, "maxlogage")
     }
 }
@@ -0,0 +1,229 @@
+//! Filters for retrieving part of the query log.
+//!
+//! `api.php` applies a single filter per request, picking the first of the time range, domain,
+//! client, query type and forward destination which is set. Any further filters are applied to
+//! the returned entries, so give clients and forward destinations as they appear in the log.
+use crate::api_types::Query;
+use crate::ftl_types::QueryType;
+use chrono::NaiveDateTime;
+use strum::IntoEnumIterator;
+
+/// Filter applied by the Pi Hole
+#[derive(Clone, Copy, PartialEq)]
+enum ServerFilter {
+    TimeRange,
+    Domain,
+    Client,
+    QueryType,
+    ForwardDestination,
+    None,
+}
+
+/// Filter for query log requests
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryFilter {
+    /// Maximum number of entries, keeping the most recent
+    pub count: Option<u32>,
+    /// Only include queries at or after this time (UTC)
+    pub from: Option<NaiveDateTime>,
+    /// Only include queries before this time (UTC)
+    pub until: Option<NaiveDateTime>,
+    /// Only include queries of this client, by IP address or hostname
+    pub client: Option<String>,
+    /// Only include queries for this domain
+    pub domain: Option<String>,
+    /// Only include queries of this type
+    pub query_type: Option<QueryType>,
+    /// Only include queries forwarded to this upstream server
+    pub forward_destination: Option<String>,
+    /// Only include blocked queries
+    pub blocked_only: bool,
+}
+
+impl QueryFilter {
+    /// Creates a filter matching the whole query log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of entries, keeping the most recent
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Only include queries between `from` (inclusive) and `until` (exclusive)
+    pub fn with_time_range(mut self, from: NaiveDateTime, until: NaiveDateTime) -> Self {
+        self.from = Some(from);
+        self.until = Some(until);
+        self
+    }
+
+    /// Only include queries at or after `from`
+    pub fn with_from(mut self, from: NaiveDateTime) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Only include queries before `until`
+    pub fn with_until(mut self, until: NaiveDateTime) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Only include queries of `client`, by IP address or hostname
+    pub fn with_client(mut self, client: &str) -> Self {
+        self.client = Some(client.to_string());
+        self
+    }
+
+    /// Only include queries for `domain`
+    pub fn with_domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    /// Only include queries of `query_type`
+    pub fn with_query_type(mut self, query_type: QueryType) -> Self {
+        self.query_type = Some(query_type);
+        self
+    }
+
+    /// Only include queries forwarded to `destination`, e.g. `8.8.8.8` or `8.8.8.8#53`
+    pub fn with_forward_destination(mut self, destination: &str) -> Self {
+        self.forward_destination = Some(destination.to_string());
+        self
+    }
+
+    /// Only include blocked queries
+    pub fn with_blocked_only(mut self) -> Self {
+        self.blocked_only = true;
+        self
+    }
+
+    /// Index of the query type used by FTL, if known
+    fn query_type_index(&self) -> Option<usize> {
+        let query_type = self.query_type.as_ref()?;
+        QueryType::iter()
+            .position(|known_type| &known_type == query_type)
+            .map(|index| index + 1)
+    }
+
+    /// Filter applied by the Pi Hole, in the order `api.php` checks them
+    fn server_filter(&self) -> ServerFilter {
+        if self.from.is_some() || self.until.is_some() {
+            ServerFilter::TimeRange
+        } else if self.domain.is_some() {
+            ServerFilter::Domain
+        } else if self.client.is_some() {
+            ServerFilter::Client
+        } else if self.query_type_index().is_some() {
+            ServerFilter::QueryType
+        } else if self.forward_destination.is_some() {
+            ServerFilter::ForwardDestination
+        } else {
+            ServerFilter::None
+        }
+    }
+
+    /// Whether any filter is left for [`apply`](Self::apply) after the Pi Hole applied `server_filter`
+    fn has_local_filter(&self, server_filter: ServerFilter) -> bool {
+        (server_filter != ServerFilter::TimeRange && (self.from.is_some() || self.until.is_some()))
+            || (server_filter != ServerFilter::Domain && self.domain.is_some())
+            || (server_filter != ServerFilter::Client
+                && (self.client.is_some() || self.blocked_only))
+            || (server_filter != ServerFilter::QueryType && self.query_type.is_some())
+            || (server_filter != ServerFilter::ForwardDestination
+                && self.forward_destination.is_some())
+    }
+
+    /// Query parameters of the `getAllQueries` request
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        let server_filter = self.server_filter();
+        // The count applies to the filtered entries, so leave it to `apply` if any filter is local
+        let count = match self.count {
+            Some(count) if !self.has_local_filter(server_filter) => count.to_string(),
+            _ => String::new(),
+        };
+        let mut params = vec![("getAllQueries", count)];
+        match server_filter {
+            ServerFilter::TimeRange => {
+                let from = self.from.map_or(0, |from| from.and_utc().timestamp());
+                let until = self
+                    .until
+                    .map_or(i64::from(u32::MAX), |until| until.and_utc().timestamp());
+                params.push(("from", from.to_string()));
+                params.push(("until", until.to_string()));
+            }
+            ServerFilter::Domain => {
+                params.push(("domain", self.domain.clone().unwrap_or_default()))
+            }
+            ServerFilter::Client => {
+                params.push(("client", self.client.clone().unwrap_or_default()));
+                if self.blocked_only {
+                    params.push(("type", "blocked".to_string()));
+                }
+            }
+            ServerFilter::QueryType => {
+                let index = self.query_type_index().unwrap_or_default();
+                params.push(("querytype", index.to_string()));
+            }
+            ServerFilter::ForwardDestination => params.push((
+                "forwarddest",
+                self.forward_destination.clone().unwrap_or_default(),
+            )),
+            ServerFilter::None => {}
+        }
+        params
+    }
+
+    /// Apply the filters the Pi Hole did not apply to `queries`
+    pub(crate) fn apply(&self, mut queries: Vec<Query>) -> Vec<Query> {
+        let server_filter = self.server_filter();
+        queries.retain(|query| {
+            (server_filter == ServerFilter::TimeRange || self.matches_time_range(query))
+                && (server_filter == ServerFilter::Domain || matches(&self.domain, &query.domain))
+                && (server_filter == ServerFilter::Client || matches(&self.client, &query.client))
+                && (server_filter == ServerFilter::QueryType
+                    || matches(&self.query_type, &query.query_type))
+                && (server_filter == ServerFilter::ForwardDestination
+                    || self.matches_forward_destination(query))
+                && (!self.blocked_only
+                    || server_filter == ServerFilter::Client
+                    || query.status.is_blocked())
+        });
+        if let Some(count) = self.count {
+            let count = count as usize;
+            if queries.len() > count {
+                queries.drain(..queries.len() - count);
+            }
+        }
+        queries
+    }
+
+    fn matches_time_range(&self, query: &Query) -> bool {
+        match (self.from, self.until) {
+            (Some(from), _) if query.timestring < from => false,
+            (_, Some(until)) if query.timestring >= until => false,
+            _ => true,
+        }
+    }
+
+    fn matches_forward_destination(&self, query: &Query) -> bool {
+        match &self.forward_destination {
+            Some(destination) => {
+                &query.upstream_destination == destination
+                    || query.upstream_destination.split('#').next() == Some(destination)
+            }
+            None => true,
+        }
+    }
+}
+
+/// Whether `value` matches `filter`, if set
+fn matches<T: PartialEq>(filter: &Option<T>, value: &T) -> bool {
+    match filter {
+        Some(filter) => filter == value,
+        None => true,
+    }
+}
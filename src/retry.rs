@@ -0,0 +1,101 @@
+//! Retry policy for idempotent requests.
+//!
+//! Only read-only endpoints are retried. Requests which modify the Pi Hole, such as
+//! `list_add` or `disable`, are always sent exactly once.
+use crate::errors::{APIError, APIErrorKind};
+use std::time::Duration;
+
+/// Retry policy with exponential backoff and jitter
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between retries
+    pub max_backoff: Duration,
+    /// Factor the delay is multiplied by after each retry
+    pub multiplier: f64,
+    /// Fraction of the delay, between 0 and 1, which is randomised to spread out retries
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    /// Policy which never retries
+    pub const NONE: RetryPolicy = RetryPolicy {
+        max_retries: 0,
+        initial_backoff: Duration::from_millis(0),
+        max_backoff: Duration::from_millis(0),
+        multiplier: 1.0,
+        jitter: 0.0,
+    };
+
+    /// Creates a policy which retries up to `max_retries` times,
+    /// starting with a 100ms delay which doubles after each retry up to 10 seconds.
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+
+    /// Set the delay before the first retry and the maximum delay between retries
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Set the factor the delay is multiplied by after each retry. Negative factors are treated as 0.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(0.0);
+        self
+    }
+
+    /// Set the fraction of the delay which is randomised. Clamped between 0 and 1.
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Get the delay before retry number `retry`, starting from 0, without jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.powi(retry as i32);
+        // A negative or NaN delay, from setting `multiplier` directly, falls back to the maximum
+        Duration::try_from_secs_f64(backoff)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+
+    /// Get the delay before retry number `retry` with jitter applied
+    pub fn backoff_with_jitter(&self, retry: u32) -> Duration {
+        let backoff = self.backoff(retry).as_secs_f64();
+        let jitter = backoff * self.jitter.clamp(0.0, 1.0) * (2.0 * fastrand::f64() - 1.0);
+        // Delays too long to represent, or NaN from setting `jitter` directly, fall back to the maximum
+        Duration::try_from_secs_f64(backoff + jitter).unwrap_or(self.max_backoff)
+    }
+
+    /// Whether the request should be attempted again after failing with `error` on retry number `retry`
+    pub fn should_retry(&self, retry: u32, error: &APIError) -> bool {
+        retry < self.max_retries && is_transient(error)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// Errors which may succeed if the request is sent again
+fn is_transient(error: &APIError) -> bool {
+    match error.kind() {
+        APIErrorKind::RequestError(_) | APIErrorKind::TransportError(_) => true,
+        #[cfg(feature = "async")]
+        APIErrorKind::AsyncRequestError(_) => true,
+        APIErrorKind::HttpStatus(status) => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
@@ -0,0 +1,111 @@
+//! Searching the lists for a domain through the `queryads.php` script of the web interface.
+//!
+//! The script runs `pihole -q` and streams its output, listing each domain list and adlist
+//! with a matching entry followed by the matching entries.
+use crate::api_types::DomainList;
+use crate::errors::APIError;
+use crate::event_stream::EventStreamLines;
+use crate::PiHoleAPIConfigWithSession;
+
+/// Path of the list search script, relative to the base path
+const QUERYADS_PATH: &str = "scripts/pi-hole/php/queryads.php";
+
+/// Wildcard entries are stored as regular expressions of this form
+const WILDCARD_PREFIX: &str = "(\\.|^)";
+
+pub trait ListSearchPiHoleAPI {
+    /// Find the list entries and adlists matching `domain`.
+    fn search_lists(&self, domain: &str, mode: SearchMode) -> Result<Vec<ListMatch>, APIError>;
+}
+
+impl ListSearchPiHoleAPI for PiHoleAPIConfigWithSession {
+    fn search_lists(&self, domain: &str, mode: SearchMode) -> Result<Vec<ListMatch>, APIError> {
+        let mut params = vec![("domain", domain)];
+        if mode == SearchMode::Exact {
+            params.push(("exact", ""));
+        }
+        let response = self.session_stream(QUERYADS_PATH, &params)?;
+        let lines = EventStreamLines::new(response).collect::<Result<Vec<String>, _>>()?;
+        Ok(parse_search_output(&lines))
+    }
+}
+
+/// How list entries are compared with the searched domain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchMode {
+    /// Entries equal to the domain
+    Exact,
+    /// Entries containing the domain
+    #[default]
+    Partial,
+}
+
+/// List or adlist containing a matching entry
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ListMatchSource {
+    /// Domain list of the Pi Hole
+    List(DomainList),
+    /// Adlist with this URL
+    Adlist(String),
+}
+
+/// Entry matching the searched domain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListMatch {
+    /// List the entry is on
+    pub source: ListMatchSource,
+    /// Domain, regular expression or wildcard domain of the entry
+    pub entry: String,
+}
+
+/// Parse the output of `pihole -q`, e.g.
+///
+/// ```text
+///  Match found in exact blacklist
+///    ads.example.com
+///  Match found in https://example.com/hosts:
+///    ads.example.com
+/// ```
+fn parse_search_output(lines: &[String]) -> Vec<ListMatch> {
+    let mut source = None;
+    let mut matches = Vec::new();
+    for line in lines {
+        if let Some(found_in) = line.split(" found in ").nth(1) {
+            source = Some(parse_source(found_in.trim_end_matches(':')));
+        } else if line.starts_with('[') {
+            // Status messages such as `[i] Over 100 results found for ...`
+            source = None;
+        } else if let Some(source) = &source {
+            let entry = line.split_whitespace().last().unwrap_or_default();
+            matches.push(ListMatch {
+                source: entry_source(source, entry),
+                entry: entry.to_string(),
+            });
+        }
+    }
+    matches
+}
+
+fn parse_source(found_in: &str) -> ListMatchSource {
+    match found_in.to_lowercase().as_str() {
+        "exact whitelist" | "whitelist" => ListMatchSource::List(DomainList::ExactAllow),
+        "exact blacklist" | "blacklist" => ListMatchSource::List(DomainList::ExactDeny),
+        "regex whitelist" => ListMatchSource::List(DomainList::RegexAllow),
+        "regex blacklist" => ListMatchSource::List(DomainList::RegexDeny),
+        _ => ListMatchSource::Adlist(found_in.to_string()),
+    }
+}
+
+/// Regex entries added as wildcards are reported on the wildcard lists
+fn entry_source(source: &ListMatchSource, entry: &str) -> ListMatchSource {
+    let is_wildcard = entry.starts_with(WILDCARD_PREFIX) && entry.ends_with('$');
+    match source {
+        ListMatchSource::List(DomainList::RegexAllow) if is_wildcard => {
+            ListMatchSource::List(DomainList::WildcardAllow)
+        }
+        ListMatchSource::List(DomainList::RegexDeny) if is_wildcard => {
+            ListMatchSource::List(DomainList::WildcardDeny)
+        }
+        source => source.clone(),
+    }
+}
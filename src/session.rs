@@ -0,0 +1,443 @@
+//! Session authenticated client for the PHP scripts of the web interface.
+//!
+//! Group, adlist and client management is handled by scripts such as `scripts/pi-hole/php/groups.php`,
+//! which require a logged in `PHPSESSID` session and the CSRF token of that session instead of the API key.
+use crate::auth::{self, ApiKeyLocation, SecretString};
+use crate::capabilities::{Capabilities, Feature};
+use crate::errors::{self, APIError, APIErrorKind};
+use crate::retry::RetryPolicy;
+#[cfg(feature = "async")]
+use crate::transport::AsyncHttpTransport;
+use crate::transport::{
+    HttpMethod, HttpRequest, HttpResponse, HttpStreamingResponse, HttpTransport, TransportOptions,
+};
+use crate::{
+    api_request, parse_json_response, require_feature, send_request_for_response, PiHoleAPIConfig,
+    PiHoleAPIHost, PiHoleAPIKey, NO_PARAMS,
+};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+const SESSION_COOKIE: &str = "PHPSESSID";
+
+/// Pi Hole API Struct authenticated with a web interface login session.
+///
+/// Logs in on the first request, keeps the session cookie and CSRF token and logs in again
+/// when the session expires. The API key is derived from the password,
+/// so the [`AuthenticatedPiHoleAPI`](crate::AuthenticatedPiHoleAPI) methods are available too.
+///
+/// Clones share the same session.
+#[derive(Clone)]
+pub struct PiHoleAPIConfigWithSession {
+    /// Host and transport configuration
+    config: PiHoleAPIConfig,
+
+    /// Web interface password
+    password: SecretString,
+
+    /// API key derived from the password
+    api_key: SecretString,
+
+    /// Current login session, shared between clones
+    session: Arc<Mutex<Option<Session>>>,
+}
+
+/// Cookie and CSRF token of a logged in session
+#[derive(Clone, Debug, PartialEq)]
+struct Session {
+    /// Value of the session cookie
+    cookie: SecretString,
+
+    /// CSRF token which must be sent with every request
+    token: SecretString,
+}
+
+impl PiHoleAPIConfigWithSession {
+    /// Creates a new Pi Hole API instance which logs in with the web interface `password`.
+    /// `host` must begin with the protocol e.g. http:// or https://
+    pub fn new(host: String, password: String) -> Self {
+        Self::from_config(PiHoleAPIConfig::new(host), password)
+    }
+
+    /// Creates a new session authenticated Pi Hole API instance from an existing config
+    pub fn from_config(config: PiHoleAPIConfig, password: String) -> Self {
+        Self {
+            config,
+            api_key: auth::api_token_from_password(&password),
+            password: SecretString::new(password),
+            session: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Set the path of the admin interface, `/admin/` by default.
+    /// Use this for Pi Holes behind a reverse proxy, e.g. `/pihole/admin/`.
+    pub fn with_base_path(mut self, base_path: &str) -> Result<Self, APIError> {
+        self.config = self.config.with_base_path(base_path)?;
+        Ok(self)
+    }
+
+    /// Send requests through `transport` instead of the default `UreqTransport`
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.config = self.config.with_transport(transport);
+        self
+    }
+
+    /// Replace the transports with the built-in transports configured with `options`
+    pub fn with_transport_options(mut self, options: &TransportOptions) -> Result<Self, APIError> {
+        self.config = self.config.with_transport_options(options)?;
+        Ok(self)
+    }
+
+    /// Retry read-only requests according to `retry_policy`.
+    /// Requests which modify the Pi Hole are never retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.config = self.config.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Refuse requests not supported by a host with `capabilities` with [`APIErrorKind::Unsupported`]
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.config = self.config.with_capabilities(capabilities);
+        self
+    }
+
+    /// Send asynchronous requests through `transport` instead of the default `ReqwestTransport`
+    #[cfg(feature = "async")]
+    pub fn with_async_transport(mut self, transport: Arc<dyn AsyncHttpTransport>) -> Self {
+        self.config = self.config.with_async_transport(transport);
+        self
+    }
+
+    /// Log in and store the new session, replacing the current one.
+    /// Called automatically by requests, but can be used to check the password.
+    pub fn login(&self) -> Result<(), APIError> {
+        let mut session = self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        *session = Some(self.new_session()?);
+        Ok(())
+    }
+
+    /// Send a form encoded POST request to the PHP script at `path`, relative to the base path,
+    /// e.g. `scripts/pi-hole/php/groups.php`. The CSRF token is added to `params`.
+    /// Returns the response body. These requests are never retried.
+    pub fn post_form(&self, path: &str, params: &[(&str, &str)]) -> Result<String, APIError> {
+        self.session_request(path, params, &RetryPolicy::NONE)
+    }
+
+    /// Send a session authenticated request, logging in again once if the session has expired
+    pub(crate) fn session_request(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+        retry_policy: &RetryPolicy,
+    ) -> Result<String, APIError> {
+        let (request, response) = self.session_form_request(path, params, retry_policy)?;
+        response
+            .text()
+            .map_err(|error| error.with_context(&request, Some(response.status)))
+    }
+
+    /// Send a session authenticated request and deserialize the JSON response,
+    /// logging in again once if the session has expired
+    pub(crate) fn session_json_request<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+        retry_policy: &RetryPolicy,
+    ) -> Result<T, APIError> {
+        let (request, response) = self.session_form_request(path, params, retry_policy)?;
+        parse_json_response(&request, &response)
+    }
+
+    /// Open a session authenticated event stream from the PHP script at `path` with the query `params`,
+    /// logging in again once if the session has expired
+    pub(crate) fn session_stream(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<HttpStreamingResponse, APIError> {
+        let request = api_request(self, HttpMethod::Get, path, params)?;
+        self.with_session(&request, |session| {
+            self.stream_with_session(session, path, params)
+        })
+    }
+
+    /// Send the request returned by `build_request` for a POST request to the PHP script at `path`
+    /// and the CSRF token, adding the session cookie and logging in again once if the session has expired.
+    /// Returns the request which was sent with its response.
+    pub(crate) fn session_send(
+        &self,
+        path: &str,
+        retry_policy: &RetryPolicy,
+        build_request: impl Fn(HttpRequest, &str) -> HttpRequest,
+    ) -> Result<(HttpRequest, HttpResponse), APIError> {
+        let request = api_request(self, HttpMethod::Post, path, &NO_PARAMS)?;
+        self.with_session(&request, |session| {
+            let request = self.with_session_cookie(
+                build_request(request.clone(), session.token.expose_secret()),
+                &session.cookie,
+            );
+            let response =
+                send_request_for_response(self.get_transport(), request.clone(), retry_policy)?;
+            let is_expired = matches!(
+                std::str::from_utf8(&response.body),
+                Ok(text) if is_session_expired(text)
+            );
+            Ok(Some((request, response)).filter(|_| !is_expired))
+        })
+    }
+
+    /// Send `params` and the CSRF token as a form to the PHP script at `path`
+    fn session_form_request(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+        retry_policy: &RetryPolicy,
+    ) -> Result<(HttpRequest, HttpResponse), APIError> {
+        self.session_send(path, retry_policy, |request, token| {
+            let body = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(params)
+                .append_pair("token", token)
+                .finish();
+            form_request(request, body)
+        })
+    }
+
+    /// Call `send` with the current session, and once more with a new session if it returns `None`
+    /// because the session has expired. Errors which occur before `request` is sent carry its context.
+    fn with_session<T>(
+        &self,
+        request: &HttpRequest,
+        send: impl Fn(&Session) -> Result<Option<T>, APIError>,
+    ) -> Result<T, APIError> {
+        // Every script of the web interface was removed in Pi-hole v6
+        require_feature(self, Feature::PhpApi, request)?;
+        let session = self.current_session()?;
+        if let Some(result) = send(&session)? {
+            return Ok(result);
+        }
+
+        let session = self.replace_expired_session(&session)?;
+        send(&session)?
+            .ok_or_else(|| APIError::from(APIErrorKind::LoginFailed).with_context(request, None))
+    }
+
+    /// Open an event stream with `session`, returning `None` if the session has expired
+    fn stream_with_session(
+        &self,
+        session: &Session,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Option<HttpStreamingResponse>, APIError> {
+        // Event streams are opened with GET requests, so the CSRF token is sent in the query
+        let mut params = params.to_vec();
+        params.push(("token", session.token.expose_secret()));
+        let request = self.with_session_cookie(
+            api_request(self, HttpMethod::Get, path, &params)?,
+            &session.cookie,
+        );
+        let response = self
+            .get_transport()
+            .send_streaming(request.clone())
+            .and_then(|response| {
+                if response.status >= 400 {
+                    return Err(APIErrorKind::HttpStatus(response.status).into());
+                }
+                Ok(response)
+            })
+            .map_err(|error| error.with_context(&request, None))?;
+        let is_event_stream = matches!(
+            response.header("Content-Type"),
+            Some(content_type) if content_type.starts_with("text/event-stream")
+        );
+        if is_event_stream {
+            return Ok(Some(response));
+        }
+
+        // Rejected requests are answered with a plain text message instead
+        let status = response.status;
+        let response_text = response
+            .into_response()
+            .and_then(|response| response.text())
+            .map_err(|error| error.with_context(&request, Some(status)))?;
+        if is_session_expired(&response_text) {
+            return Ok(None);
+        }
+        errors::detect_response_errors(&response_text)
+            .map_err(|error| error.with_context(&request, Some(status)))?;
+        Err(
+            APIError::from(APIErrorKind::ServerError(response_text.trim().to_string()))
+                .with_context(&request, Some(status)),
+        )
+    }
+
+    /// Get the current session, logging in if there is none
+    fn current_session(&self) -> Result<Session, APIError> {
+        let mut session = self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        if session.is_none() {
+            *session = Some(self.new_session()?);
+        }
+        session
+            .clone()
+            .ok_or_else(|| APIErrorKind::LoginFailed.into())
+    }
+
+    /// Log in again unless another clone already replaced the `expired` session
+    fn replace_expired_session(&self, expired: &Session) -> Result<Session, APIError> {
+        let mut session = self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        match session.as_ref() {
+            Some(current) if current != expired => Ok(current.clone()),
+            _ => {
+                let new_session = self.new_session()?;
+                *session = Some(new_session.clone());
+                Ok(new_session)
+            }
+        }
+    }
+
+    /// Start a session, log in with the password and read the CSRF token of the session
+    fn new_session(&self) -> Result<Session, APIError> {
+        let transport = self.get_transport();
+        let login_failed = |request: &HttpRequest, response: &HttpResponse| {
+            APIError::from(APIErrorKind::LoginFailed).with_context(request, Some(response.status))
+        };
+
+        // Loading the login page starts a new session
+        let request = api_request(self, HttpMethod::Get, "login.php", &NO_PARAMS)?;
+        let response = send_request_for_response(transport, request.clone(), &RetryPolicy::NONE)?;
+        let cookie = session_cookie(&response).ok_or_else(|| login_failed(&request, &response))?;
+
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("pw", self.password.expose_secret())
+            .finish();
+        let request = self.with_session_cookie(
+            form_request(
+                api_request(self, HttpMethod::Post, "login.php", &NO_PARAMS)?,
+                body,
+            ),
+            &cookie,
+        );
+        let response = send_request_for_response(transport, request, &RetryPolicy::NONE)?;
+        // The session may be renewed on login
+        let cookie = session_cookie(&response).unwrap_or(cookie);
+
+        // The CSRF token is only included in pages served to a logged in session
+        let request = self.with_session_cookie(
+            api_request(self, HttpMethod::Get, "index.php", &NO_PARAMS)?,
+            &cookie,
+        );
+        let response = send_request_for_response(transport, request.clone(), &RetryPolicy::NONE)?;
+        let token = response
+            .text()
+            .ok()
+            .and_then(|page| scrape_token(&page))
+            .ok_or_else(|| login_failed(&request, &response))?;
+        Ok(Session { cookie, token })
+    }
+
+    fn with_session_cookie(&self, request: HttpRequest, cookie: &SecretString) -> HttpRequest {
+        request.with_header(
+            "Cookie",
+            &format!("{}={}", SESSION_COOKIE, cookie.expose_secret()),
+        )
+    }
+}
+
+impl fmt::Debug for PiHoleAPIConfigWithSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let logged_in = self
+            .session
+            .lock()
+            .map(|session| session.is_some())
+            .unwrap_or(false);
+        f.debug_struct("PiHoleAPIConfigWithSession")
+            .field("host", &self.config.host)
+            .field("base_path", &self.config.base_path)
+            .field("logged_in", &logged_in)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PiHoleAPIHost for PiHoleAPIConfigWithSession {
+    fn get_host(&self) -> &str {
+        self.config.get_host()
+    }
+
+    fn get_base_path(&self) -> &str {
+        self.config.get_base_path()
+    }
+
+    fn get_transport(&self) -> &dyn HttpTransport {
+        self.config.get_transport()
+    }
+
+    fn get_retry_policy(&self) -> &RetryPolicy {
+        self.config.get_retry_policy()
+    }
+
+    fn get_capabilities(&self) -> Option<&Capabilities> {
+        self.config.get_capabilities()
+    }
+
+    #[cfg(feature = "async")]
+    fn get_async_transport(&self) -> &dyn AsyncHttpTransport {
+        self.config.get_async_transport()
+    }
+}
+
+impl PiHoleAPIKey for PiHoleAPIConfigWithSession {
+    fn get_api_key(&self) -> &str {
+        self.api_key.expose_secret()
+    }
+
+    fn get_api_key_location(&self) -> ApiKeyLocation {
+        ApiKeyLocation::default()
+    }
+}
+
+/// Send `body` as a form with `request`
+fn form_request(mut request: HttpRequest, body: String) -> HttpRequest {
+    request.body = Some(body.into_bytes());
+    request.with_header("Content-Type", "application/x-www-form-urlencoded")
+}
+
+/// Get the session cookie from the `Set-Cookie` headers of `response`
+fn session_cookie(response: &HttpResponse) -> Option<SecretString> {
+    response.headers_all("Set-Cookie").find_map(|cookie| {
+        let (name, value) = cookie.split(';').next()?.split_once('=')?;
+        if name.trim() == SESSION_COOKIE && !value.trim().is_empty() {
+            Some(SecretString::new(value.trim().to_string()))
+        } else {
+            None
+        }
+    })
+}
+
+/// Get the CSRF token from the `<div id="token">` element of a web interface page
+fn scrape_token(page: &str) -> Option<SecretString> {
+    let element = &page[page.find("id=\"token\"")?..];
+    let content = &element[element.find('>')? + 1..];
+    let token = content[..content.find('<')?].trim();
+    if token.is_empty() {
+        None
+    } else {
+        Some(SecretString::new(token.to_string()))
+    }
+}
+
+/// Whether a PHP script rejected the request because the session or CSRF token is no longer valid
+fn is_session_expired(response_text: &str) -> bool {
+    response_text.starts_with("Not allowed (login session invalid or expired")
+        || response_text.starts_with("Session expired!")
+        || response_text.starts_with("Wrong token!")
+        || response_text.starts_with("Not authorized")
+}
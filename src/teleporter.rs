@@ -0,0 +1,291 @@
+//! Backup and restore through the `teleporter.php` script of the web interface.
+//!
+//! The backup is a `.tar.gz` archive of the lists, groups, clients, local DNS records and
+//! static DHCP leases, as downloaded from Settings → Teleporter.
+use crate::errors::{APIError, APIErrorKind};
+use crate::retry::RetryPolicy;
+use crate::{PiHoleAPIConfigWithSession, PiHoleAPIHost};
+use strum::{EnumIter, IntoEnumIterator};
+
+/// Path of the teleporter script, relative to the base path
+const TELEPORTER_PATH: &str = "scripts/pi-hole/php/teleporter.php";
+
+/// File name used if the Pi Hole does not name the archive
+const DEFAULT_FILE_NAME: &str = "pi-hole-teleporter.tar.gz";
+
+/// Start of every gzip file
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+pub trait TeleporterPiHoleAPI {
+    /// Download a backup archive of the configuration.
+    fn export_teleporter(&self) -> Result<TeleporterArchive, APIError>;
+
+    /// Restore the parts of `archive` selected in `import`.
+    /// Returns the messages reported for each part, e.g. `Processed adlist (3 entries)`.
+    fn import_teleporter(
+        &self,
+        archive: &TeleporterArchive,
+        import: &TeleporterImport,
+    ) -> Result<Vec<String>, APIError>;
+}
+
+impl TeleporterPiHoleAPI for PiHoleAPIConfigWithSession {
+    fn export_teleporter(&self) -> Result<TeleporterArchive, APIError> {
+        let (request, response) = self.session_send(
+            TELEPORTER_PATH,
+            self.get_retry_policy(),
+            |mut request, token| {
+                let body = url::form_urlencoded::Serializer::new(String::new())
+                    .append_pair("token", token)
+                    .finish();
+                request.body = Some(body.into_bytes());
+                request.with_header("Content-Type", "application/x-www-form-urlencoded")
+            },
+        )?;
+        if !response.body.starts_with(&GZIP_MAGIC) {
+            // Failures are reported as a plain text message instead of the archive
+            let error = match response.text() {
+                Ok(message) => APIErrorKind::ServerError(message.trim().to_string()).into(),
+                Err(error) => error,
+            };
+            return Err(error.with_context(&request, Some(response.status)));
+        }
+
+        let file_name = response
+            .header("Content-Disposition")
+            .and_then(attachment_file_name)
+            .unwrap_or_else(|| DEFAULT_FILE_NAME.to_string());
+        Ok(TeleporterArchive {
+            file_name,
+            data: response.body,
+        })
+    }
+
+    fn import_teleporter(
+        &self,
+        archive: &TeleporterArchive,
+        import: &TeleporterImport,
+    ) -> Result<Vec<String>, APIError> {
+        let (request, response) =
+            self.session_send(TELEPORTER_PATH, &RetryPolicy::NONE, |mut request, token| {
+                let mut form = MultipartForm::new(&archive.data);
+                form.field("token", token);
+                form.field("action", "in");
+                for part in &import.parts {
+                    form.field(part.form_field(), "true");
+                }
+                if import.flush_tables {
+                    form.field("flushtables", "true");
+                }
+                form.file("zip_file", &archive.file_name, &archive.data);
+                let content_type = form.content_type();
+                request.body = Some(form.finish());
+                request.with_header("Content-Type", &content_type)
+            })?;
+        response
+            .text()
+            .and_then(|response_text| parse_import_response(&response_text))
+            .map_err(|error| error.with_context(&request, Some(response.status)))
+    }
+}
+
+/// Teleporter backup archive
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TeleporterArchive {
+    /// File name, e.g. `pi-hole-raspberrypi-teleporter_2022-06-01_12-00-00.tar.gz`
+    pub file_name: String,
+    /// Contents of the `.tar.gz` archive
+    pub data: Vec<u8>,
+}
+
+impl TeleporterArchive {
+    /// Creates an archive from the contents of a `.tar.gz` file, e.g. read from an earlier backup
+    pub fn new(file_name: &str, data: Vec<u8>) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            data,
+        }
+    }
+}
+
+/// Part of the configuration which can be restored from a backup
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum TeleporterPart {
+    /// Exact allowlist
+    Whitelist,
+    /// Regex and wildcard allowlist
+    RegexWhitelist,
+    /// Exact denylist
+    Blacklist,
+    /// Regex and wildcard denylist
+    RegexBlacklist,
+    /// Adlists
+    Adlists,
+    /// Domains marked as reviewed in the audit log
+    AuditLog,
+    /// Groups
+    Groups,
+    /// Clients
+    Clients,
+    /// Custom DNS records
+    CustomDns,
+    /// Custom CNAME records
+    CustomCname,
+    /// Static DHCP leases
+    StaticDhcpLeases,
+}
+
+impl TeleporterPart {
+    /// Name of the checkbox selecting this part in the web interface
+    fn form_field(&self) -> &'static str {
+        match self {
+            TeleporterPart::Whitelist => "whitelist",
+            TeleporterPart::RegexWhitelist => "regex_whitelist",
+            TeleporterPart::Blacklist => "blacklist",
+            TeleporterPart::RegexBlacklist => "regexlist",
+            TeleporterPart::Adlists => "adlist",
+            TeleporterPart::AuditLog => "auditlog",
+            TeleporterPart::Groups => "group",
+            TeleporterPart::Clients => "client",
+            TeleporterPart::CustomDns => "localdnsrecords",
+            TeleporterPart::CustomCname => "localcnamerecords",
+            TeleporterPart::StaticDhcpLeases => "staticdhcpleases",
+        }
+    }
+}
+
+/// Parts of a backup to restore
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TeleporterImport {
+    /// Parts to restore
+    pub parts: Vec<TeleporterPart>,
+    /// Delete the existing entries of each restored part first, instead of adding to them
+    pub flush_tables: bool,
+}
+
+impl TeleporterImport {
+    /// Creates an import restoring nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an import restoring every part
+    pub fn all() -> Self {
+        Self {
+            parts: TeleporterPart::iter().collect(),
+            flush_tables: false,
+        }
+    }
+
+    /// Restore `part`
+    pub fn with_part(mut self, part: TeleporterPart) -> Self {
+        if !self.parts.contains(&part) {
+            self.parts.push(part);
+        }
+        self
+    }
+
+    /// Restore the exact and regex allow and deny lists
+    pub fn with_lists(self) -> Self {
+        self.with_part(TeleporterPart::Whitelist)
+            .with_part(TeleporterPart::RegexWhitelist)
+            .with_part(TeleporterPart::Blacklist)
+            .with_part(TeleporterPart::RegexBlacklist)
+    }
+
+    /// Delete the existing entries of each restored part first
+    pub fn with_flush_tables(mut self) -> Self {
+        self.flush_tables = true;
+        self
+    }
+}
+
+/// Get the file name from a `Content-Disposition: attachment; filename="..."` header
+fn attachment_file_name(content_disposition: &str) -> Option<String> {
+    content_disposition.split(';').find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case("filename") {
+            let value = value.trim().trim_matches('"');
+            // Only keep the file name, in case the header contains a path
+            let file_name = value.rsplit(['/', '\\']).next()?;
+            Some(file_name.to_string()).filter(|file_name| !file_name.is_empty())
+        } else {
+            None
+        }
+    })
+}
+
+/// The script reports each restored part on a line and ends with `OK` on success
+fn parse_import_response(response_text: &str) -> Result<Vec<String>, APIError> {
+    let mut messages: Vec<String> = response_text
+        .split("<br>")
+        .flat_map(str::lines)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    if messages.last().map(String::as_str) == Some("OK") {
+        messages.pop();
+        Ok(messages)
+    } else {
+        Err(APIErrorKind::ServerError(messages.join("\n")).into())
+    }
+}
+
+/// Builds a `multipart/form-data` request body
+struct MultipartForm {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl MultipartForm {
+    /// Creates a form with a boundary which does not occur in `file_data`
+    fn new(file_data: &[u8]) -> Self {
+        let boundary = (0..)
+            .map(|attempt| format!("pi-hole-api-boundary-{}", attempt))
+            .find(|boundary| {
+                !file_data
+                    .windows(boundary.len())
+                    .any(|window| window == boundary.as_bytes())
+            })
+            .unwrap_or_default();
+        Self {
+            boundary,
+            body: Vec::new(),
+        }
+    }
+
+    fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    fn field(&mut self, name: &str, value: &str) {
+        self.body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                self.boundary, name, value
+            )
+            .as_bytes(),
+        );
+    }
+
+    fn file(&mut self, name: &str, file_name: &str, data: &[u8]) {
+        self.body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: application/gzip\r\n\r\n",
+                self.boundary,
+                name,
+                file_name.replace('"', "")
+            )
+            .as_bytes(),
+        );
+        self.body.extend_from_slice(data);
+        self.body.extend_from_slice(b"\r\n");
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        self.body
+    }
+}
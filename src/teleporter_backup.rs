@@ -0,0 +1,247 @@
+//! Offline parser for teleporter backup archives.
+//!
+//! Reads the database tables and config files of a `.tar.gz` archive downloaded with
+//! [`export_teleporter`](crate::teleporter::TeleporterPiHoleAPI::export_teleporter) or from
+//! Settings → Teleporter, without a running Pi Hole. Parts missing from the archive are left empty.
+use crate::api_types::*;
+use crate::errors::{APIError, APIErrorKind};
+use crate::teleporter::TeleporterArchive;
+use crate::{parse_custom_cname_record, parse_custom_dns_record};
+use flate2::read::GzDecoder;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::Path;
+
+/// Contents of a teleporter backup
+#[derive(Serialize, Debug, Default)]
+pub struct TeleporterBackup {
+    /// Exact allowlist
+    pub whitelist: Vec<CustomListDomainDetails>,
+    /// Regex and wildcard allowlist
+    pub regex_whitelist: Vec<CustomListDomainDetails>,
+    /// Exact denylist
+    pub blacklist: Vec<CustomListDomainDetails>,
+    /// Regex and wildcard denylist
+    pub regex_blacklist: Vec<CustomListDomainDetails>,
+    /// Adlists
+    pub adlists: Vec<Adlist>,
+    /// Groups
+    pub groups: Vec<Group>,
+    /// Clients
+    pub clients: Vec<GroupClient>,
+    /// Domains marked as reviewed in the audit log
+    pub audit_log: Vec<String>,
+    /// Custom DNS records
+    pub custom_dns_records: Vec<CustomDNSRecord>,
+    /// Custom CNAME records
+    pub custom_cname_records: Vec<CustomCNAMERecord>,
+    /// Static DHCP leases
+    pub static_dhcp_leases: Vec<StaticDhcpLease>,
+    /// Settings from `setupVars.conf`
+    pub setup_vars: BTreeMap<String, String>,
+    /// Settings from `pihole-FTL.conf`
+    pub ftl_config: BTreeMap<String, String>,
+}
+
+/// Row of a `*_by_group` table
+#[derive(Deserialize)]
+struct GroupAssignment {
+    #[serde(alias = "domainlist_id", alias = "adlist_id", alias = "client_id")]
+    id: u64,
+    group_id: u64,
+}
+
+/// Row of the audit log table
+#[derive(Deserialize)]
+struct AuditLogEntry {
+    domain: String,
+}
+
+impl TeleporterBackup {
+    /// Parse the contents of a teleporter `.tar.gz` archive
+    pub fn parse(data: &[u8]) -> Result<Self, APIError> {
+        let files = read_files(data).map_err(|error| {
+            APIErrorKind::InvalidResponse(format!("Invalid teleporter archive: {}", error))
+        })?;
+
+        let mut backup = TeleporterBackup {
+            whitelist: parse_table(&files, "whitelist.exact.json")?,
+            regex_whitelist: parse_table(&files, "whitelist.regex.json")?,
+            blacklist: parse_table(&files, "blacklist.exact.json")?,
+            regex_blacklist: parse_table(&files, "blacklist.regex.json")?,
+            adlists: parse_table(&files, "adlist.json")?,
+            groups: parse_table(&files, "group.json")?,
+            clients: parse_table(&files, "client.json")?,
+            audit_log: parse_table::<AuditLogEntry>(&files, "domain_audit.json")?
+                .into_iter()
+                .map(|entry| entry.domain)
+                .collect(),
+            custom_dns_records: parse_config_lines(&files, "custom.list", |line| {
+                // `IP domain`, as in a hosts file
+                let mut fields = line.split_whitespace();
+                let ip_address = fields.next().unwrap_or_default().to_string();
+                let domain = fields.next().unwrap_or_default().to_string();
+                parse_custom_dns_record(vec![domain, ip_address])
+            })?,
+            custom_cname_records: parse_config_lines(
+                &files,
+                "05-pihole-custom-cname.conf",
+                |line| {
+                    // `cname=domain,target`
+                    let record = line.trim_start_matches("cname=");
+                    parse_custom_cname_record(record.split(',').map(str::to_string).collect())
+                },
+            )?,
+            static_dhcp_leases: parse_config_lines(
+                &files,
+                "04-pihole-static-dhcp.conf",
+                parse_static_dhcp_lease,
+            )?,
+            setup_vars: parse_key_values(&files, "setupVars.conf"),
+            ftl_config: parse_key_values(&files, "pihole-FTL.conf"),
+        };
+
+        let domain_groups = parse_group_assignments(&files, "domainlist_by_group.json")?;
+        for domain in backup
+            .whitelist
+            .iter_mut()
+            .chain(backup.regex_whitelist.iter_mut())
+            .chain(backup.blacklist.iter_mut())
+            .chain(backup.regex_blacklist.iter_mut())
+        {
+            assign_groups(&domain_groups, domain.id, &mut domain.groups);
+        }
+        let adlist_groups = parse_group_assignments(&files, "adlist_by_group.json")?;
+        for adlist in &mut backup.adlists {
+            assign_groups(&adlist_groups, adlist.id, &mut adlist.groups);
+        }
+        let client_groups = parse_group_assignments(&files, "client_by_group.json")?;
+        for client in &mut backup.clients {
+            assign_groups(&client_groups, client.id, &mut client.groups);
+        }
+        Ok(backup)
+    }
+
+    /// Parse a downloaded teleporter archive
+    pub fn from_archive(archive: &TeleporterArchive) -> Result<Self, APIError> {
+        Self::parse(&archive.data)
+    }
+
+    /// Read and parse the teleporter archive at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, APIError> {
+        Self::parse(&std::fs::read(path)?)
+    }
+}
+
+/// Read the files of the archive by file name, ignoring their directories
+fn read_files(data: &[u8]) -> std::io::Result<HashMap<String, Vec<u8>>> {
+    let mut archive = tar::Archive::new(GzDecoder::new(data));
+    let mut files = HashMap::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let file_name = entry
+            .path()?
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned());
+        if let Some(file_name) = file_name {
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            files.insert(file_name, contents);
+        }
+    }
+    Ok(files)
+}
+
+/// Parse the JSON array of table rows in `file_name`
+fn parse_table<T: DeserializeOwned>(
+    files: &HashMap<String, Vec<u8>>,
+    file_name: &str,
+) -> Result<Vec<T>, APIError> {
+    match files.get(file_name) {
+        Some(contents) => serde_json::from_slice(contents).map_err(|error| {
+            APIErrorKind::InvalidResponse(format!(
+                "Invalid {} in teleporter archive: {}",
+                file_name, error
+            ))
+            .into()
+        }),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Parse each line of `file_name` which is not empty or a comment
+fn parse_config_lines<T>(
+    files: &HashMap<String, Vec<u8>>,
+    file_name: &str,
+    parse_line: impl Fn(&str) -> Result<T, APIError>,
+) -> Result<Vec<T>, APIError> {
+    config_lines(files, file_name).map(parse_line).collect()
+}
+
+fn config_lines<'a>(
+    files: &'a HashMap<String, Vec<u8>>,
+    file_name: &str,
+) -> impl Iterator<Item = &'a str> {
+    files
+        .get(file_name)
+        .and_then(|contents| std::str::from_utf8(contents).ok())
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Parse the `KEY=value` lines of `file_name`
+fn parse_key_values(files: &HashMap<String, Vec<u8>>, file_name: &str) -> BTreeMap<String, String> {
+    config_lines(files, file_name)
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Parse a `dhcp-host=MAC,IP,hostname` line, where the IP address and hostname are optional
+fn parse_static_dhcp_lease(line: &str) -> Result<StaticDhcpLease, APIError> {
+    let mut fields = line.trim_start_matches("dhcp-host=").split(',');
+    let mac_address = fields.next().unwrap_or_default().to_string();
+    let mut lease = StaticDhcpLease {
+        mac_address,
+        ip_address: None,
+        hostname: None,
+    };
+    for field in fields.filter(|field| !field.is_empty()) {
+        match field.parse() {
+            Ok(ip_address) => lease.ip_address = Some(ip_address),
+            Err(_) => lease.hostname = Some(field.to_string()),
+        }
+    }
+    Ok(lease)
+}
+
+/// Parse a `*_by_group` table into the group IDs of each entry
+fn parse_group_assignments(
+    files: &HashMap<String, Vec<u8>>,
+    file_name: &str,
+) -> Result<Option<HashMap<u64, Vec<u64>>>, APIError> {
+    if !files.contains_key(file_name) {
+        return Ok(None);
+    }
+    let mut assignments: HashMap<u64, Vec<u64>> = HashMap::new();
+    for row in parse_table::<GroupAssignment>(files, file_name)? {
+        assignments.entry(row.id).or_default().push(row.group_id);
+    }
+    Ok(Some(assignments))
+}
+
+/// Set `groups` from the assignment table, if the archive included it
+fn assign_groups(assignments: &Option<HashMap<u64, Vec<u64>>>, id: u64, groups: &mut Vec<u64>) {
+    if let Some(assignments) = assignments {
+        let mut assigned = assignments.get(&id).cloned().unwrap_or_default();
+        assigned.sort_unstable();
+        *groups = assigned;
+    }
+}
@@ -0,0 +1,189 @@
+//! TLS settings for Pi Holes served over HTTPS.
+//!
+//! By default certificates are verified against the Mozilla root certificates. [`TlsOptions`] can add
+//! extra root certificates, pin the certificate of the Pi Hole by its SHA-256 fingerprint, or turn
+//! verification off entirely. The same settings are used by both the blocking and async transports.
+use crate::errors::{APIError, APIErrorKind};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, RootCertStore, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// TLS settings for the built-in transports
+#[derive(Clone, Debug, Default)]
+pub struct TlsOptions {
+    /// Extra trusted root certificates in DER format
+    root_certificates: Vec<CertificateDer<'static>>,
+    /// SHA-256 fingerprints of accepted server certificates
+    pinned_fingerprints: Vec<[u8; 32]>,
+    /// Accept any certificate
+    accept_invalid_certs: bool,
+}
+
+impl TlsOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust the root certificates in `pem` in addition to the default root certificates.
+    /// Use this for Pi Holes with certificates issued by an internal CA or self-signed certificates.
+    pub fn add_root_certificates_pem(mut self, pem: &[u8]) -> Result<Self, APIError> {
+        let certificates = CertificateDer::pem_slice_iter(pem)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| APIErrorKind::InvalidTlsConfig(error.to_string()))?;
+        if certificates.is_empty() {
+            return Err(APIErrorKind::InvalidTlsConfig(
+                "No certificates found in PEM data".to_string(),
+            )
+            .into());
+        }
+        self.root_certificates.extend(certificates);
+        Ok(self)
+    }
+
+    /// Only accept a server certificate with the given SHA-256 fingerprint, written as hex
+    /// with optional `:` separators, e.g. `AB:CD:...`. Pinning a certificate accepts it even if it is not
+    /// signed by a trusted root certificate. Can be called multiple times to accept several certificates.
+    pub fn pin_certificate_sha256(mut self, fingerprint: &str) -> Result<Self, APIError> {
+        let hex: String = fingerprint.chars().filter(|c| *c != ':').collect();
+        let invalid_fingerprint = || {
+            APIErrorKind::InvalidTlsConfig(format!("Invalid SHA-256 fingerprint: {}", fingerprint))
+        };
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(invalid_fingerprint().into());
+        }
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)
+                .map_err(|_| invalid_fingerprint())?;
+        }
+        self.pinned_fingerprints.push(bytes);
+        Ok(self)
+    }
+
+    /// Accept any certificate presented by the server, including expired and self-signed ones.
+    ///
+    /// This disables protection against man-in-the-middle attacks and exposes the API key.
+    /// Prefer [`add_root_certificates_pem`](Self::add_root_certificates_pem) or
+    /// [`pin_certificate_sha256`](Self::pin_certificate_sha256).
+    pub fn danger_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Whether the options differ from the default verification
+    pub fn is_customised(&self) -> bool {
+        !self.root_certificates.is_empty()
+            || !self.pinned_fingerprints.is_empty()
+            || self.accept_invalid_certs
+    }
+
+    /// Build a rustls client config for these options
+    pub(crate) fn client_config(&self) -> Result<rustls::ClientConfig, APIError> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        for certificate in &self.root_certificates {
+            roots
+                .add(certificate.clone())
+                .map_err(|error| APIErrorKind::InvalidTlsConfig(error.to_string()))?;
+        }
+        let webpki_verifier =
+            WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .build()
+                .map_err(|error| APIErrorKind::InvalidTlsConfig(error.to_string()))?;
+        let verifier = PiHoleCertVerifier {
+            webpki_verifier,
+            pinned_fingerprints: self.pinned_fingerprints.clone(),
+            accept_invalid_certs: self.accept_invalid_certs,
+            provider: provider.clone(),
+        };
+
+        Ok(rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|error| APIErrorKind::InvalidTlsConfig(error.to_string()))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth())
+    }
+}
+
+/// Certificate verifier applying pinning and the accept invalid certificates option
+/// on top of the standard WebPKI verification
+#[derive(Debug)]
+struct PiHoleCertVerifier {
+    webpki_verifier: Arc<WebPkiServerVerifier>,
+    pinned_fingerprints: Vec<[u8; 32]>,
+    accept_invalid_certs: bool,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PiHoleCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if self.accept_invalid_certs {
+            return Ok(ServerCertVerified::assertion());
+        }
+        if !self.pinned_fingerprints.is_empty() {
+            let fingerprint: [u8; 32] = Sha256::digest(end_entity.as_ref()).into();
+            return if self.pinned_fingerprints.contains(&fingerprint) {
+                Ok(ServerCertVerified::assertion())
+            } else {
+                Err(rustls::Error::General(
+                    "Certificate does not match any pinned fingerprint".to_string(),
+                ))
+            };
+        }
+        self.webpki_verifier.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
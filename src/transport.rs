@@ -0,0 +1,523 @@
+//! HTTP transports used to send requests to the Pi Hole.
+//!
+//! The config structs send every request through an [`HttpTransport`]. [`UreqTransport`] is used by default;
+//! implement the trait to use a different HTTP stack or wrap requests in middleware.
+//! [`MemoryTransport`] replays canned responses, which is useful for tests.
+use crate::errors::{APIError, APIErrorKind};
+use crate::tls::TlsOptions;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// HTTP method of a request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl HttpMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Delete => "DELETE",
+        }
+    }
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// HTTP request to be sent by a transport
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    /// Request method
+    pub method: HttpMethod,
+    /// Full URL including the query string
+    pub url: url::Url,
+    /// Request headers
+    pub headers: Vec<(String, String)>,
+    /// Request body
+    pub body: Option<Vec<u8>>,
+}
+
+impl HttpRequest {
+    /// Creates a `method` request for `url`, with an empty body for POST and PUT requests
+    pub fn new(method: HttpMethod, url: url::Url) -> Self {
+        let body = match method {
+            HttpMethod::Post | HttpMethod::Put => Some(Vec::new()),
+            HttpMethod::Get | HttpMethod::Delete => None,
+        };
+        Self {
+            method,
+            url,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// Creates a GET request for `url`
+    pub fn get(url: url::Url) -> Self {
+        Self {
+            method: HttpMethod::Get,
+            url,
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Creates a POST request for `url` with `body`
+    pub fn post(url: url::Url, body: Vec<u8>) -> Self {
+        Self {
+            method: HttpMethod::Post,
+            url,
+            headers: Vec::new(),
+            body: Some(body),
+        }
+    }
+
+    /// Creates a PUT request for `url` with `body`
+    pub fn put(url: url::Url, body: Vec<u8>) -> Self {
+        Self {
+            method: HttpMethod::Put,
+            url,
+            headers: Vec::new(),
+            body: Some(body),
+        }
+    }
+
+    /// Creates a DELETE request for `url`
+    pub fn delete(url: url::Url) -> Self {
+        Self {
+            method: HttpMethod::Delete,
+            url,
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Adds a header to the request
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// HTTP response returned by a transport
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    /// Status code
+    pub status: u16,
+    /// Response headers
+    pub headers: Vec<(String, String)>,
+    /// Response body
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Creates a response with the given status code and body
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Adds a header to the response
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Get the first value of the header `name`, compared case insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get all values of the header `name`, compared case insensitively
+    pub fn headers_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.headers
+            .iter()
+            .filter(move |(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get the body as UTF-8 text
+    pub fn text(&self) -> Result<String, APIError> {
+        String::from_utf8(self.body.clone()).map_err(|error| {
+            APIErrorKind::IntoJsonError(std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+                .into()
+        })
+    }
+}
+
+/// HTTP response whose body is read as it is received, used for event streams
+pub struct HttpStreamingResponse {
+    /// Status code
+    pub status: u16,
+    /// Response headers
+    pub headers: Vec<(String, String)>,
+    /// Response body
+    pub body: Box<dyn Read + Send>,
+}
+
+impl HttpStreamingResponse {
+    /// Get the first value of the header `name`, compared case insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Read the rest of the body
+    pub fn into_response(mut self) -> Result<HttpResponse, APIError> {
+        let mut body = Vec::new();
+        self.body.read_to_end(&mut body)?;
+        Ok(HttpResponse {
+            status: self.status,
+            headers: self.headers,
+            body,
+        })
+    }
+}
+
+impl From<HttpResponse> for HttpStreamingResponse {
+    fn from(response: HttpResponse) -> Self {
+        Self {
+            status: response.status,
+            headers: response.headers,
+            body: Box::new(std::io::Cursor::new(response.body)),
+        }
+    }
+}
+
+impl fmt::Debug for HttpStreamingResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpStreamingResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends HTTP requests on behalf of the API config structs.
+///
+/// Implementations should return `Ok` for any response received from the server,
+/// including error status codes, and only return `Err` when no response was received.
+pub trait HttpTransport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError>;
+
+    /// Send `request` and return the response before its body has been received.
+    /// By default the whole response is received first, as with [`send`](Self::send).
+    fn send_streaming(&self, request: HttpRequest) -> Result<HttpStreamingResponse, APIError> {
+        self.send(request).map(HttpStreamingResponse::from)
+    }
+}
+
+impl<T: HttpTransport + ?Sized> HttpTransport for Arc<T> {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        (**self).send(request)
+    }
+
+    fn send_streaming(&self, request: HttpRequest) -> Result<HttpStreamingResponse, APIError> {
+        (**self).send_streaming(request)
+    }
+}
+
+/// Asynchronous version of [`HttpTransport`]
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncHttpTransport: Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError>;
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<T: AsyncHttpTransport + ?Sized> AsyncHttpTransport for Arc<T> {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        (**self).send(request).await
+    }
+}
+
+/// Connection settings for the built-in transports
+#[derive(Clone, Debug, Default)]
+pub struct TransportOptions {
+    /// Maximum time to wait for a connection to be established
+    pub timeout_connect: Option<Duration>,
+    /// Maximum time to wait for data to be received
+    pub timeout_read: Option<Duration>,
+    /// Maximum number of idle connections kept open per host
+    pub max_idle_connections_per_host: Option<usize>,
+    /// TLS certificate verification settings
+    pub tls: TlsOptions,
+}
+
+impl TransportOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum time to wait for a connection to be established
+    pub fn with_timeout_connect(mut self, timeout: Duration) -> Self {
+        self.timeout_connect = Some(timeout);
+        self
+    }
+
+    /// Set the maximum time to wait for data to be received
+    pub fn with_timeout_read(mut self, timeout: Duration) -> Self {
+        self.timeout_read = Some(timeout);
+        self
+    }
+
+    /// Set the maximum number of idle connections kept open per host
+    pub fn with_max_idle_connections_per_host(mut self, max_idle_connections: usize) -> Self {
+        self.max_idle_connections_per_host = Some(max_idle_connections);
+        self
+    }
+
+    /// Set the TLS certificate verification settings
+    pub fn with_tls(mut self, tls: TlsOptions) -> Self {
+        self.tls = tls;
+        self
+    }
+}
+
+/// Default transport backed by `ureq`.
+///
+/// Connections are kept alive and reused between requests. Clones share the same connection pool.
+#[derive(Clone, Debug)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl UreqTransport {
+    pub fn new() -> Self {
+        Self {
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    /// Creates a transport using the timeouts and TLS settings in `options`
+    pub fn with_options(options: &TransportOptions) -> Result<Self, APIError> {
+        let mut builder = ureq::AgentBuilder::new();
+        if let Some(timeout) = options.timeout_connect {
+            builder = builder.timeout_connect(timeout);
+        }
+        if let Some(timeout) = options.timeout_read {
+            builder = builder.timeout_read(timeout);
+        }
+        if let Some(max_idle_connections) = options.max_idle_connections_per_host {
+            builder = builder.max_idle_connections_per_host(max_idle_connections);
+        }
+        if options.tls.is_customised() {
+            builder = builder.tls_config(Arc::new(options.tls.client_config()?));
+        }
+        Ok(Self {
+            agent: builder.build(),
+        })
+    }
+}
+
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpTransport for UreqTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        self.send_streaming(request)?.into_response()
+    }
+
+    fn send_streaming(&self, request: HttpRequest) -> Result<HttpStreamingResponse, APIError> {
+        let mut ureq_request = self
+            .agent
+            .request_url(request.method.as_str(), &request.url);
+        for (name, value) in &request.headers {
+            ureq_request = ureq_request.set(name, value);
+        }
+        let result = match &request.body {
+            Some(body) => ureq_request.send_bytes(body),
+            None => ureq_request.call(),
+        };
+        let response = match result {
+            Ok(response) => response,
+            // Error statuses are still responses, the caller decides how to handle them
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(error) => return Err(error.into()),
+        };
+
+        let status = response.status();
+        // Repeated headers such as Set-Cookie are kept as separate entries
+        let mut names = response.headers_names();
+        names.sort();
+        names.dedup();
+        let headers = names
+            .into_iter()
+            .flat_map(|name| {
+                response
+                    .all(&name)
+                    .into_iter()
+                    .map(|value| (name.clone(), value.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Ok(HttpStreamingResponse {
+            status,
+            headers,
+            body: Box::new(response.into_reader()),
+        })
+    }
+}
+
+/// Default asynchronous transport backed by `reqwest`.
+///
+/// Connections are kept alive and reused between requests. Clones share the same connection pool.
+#[cfg(feature = "async")]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "async")]
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a transport using the timeouts and TLS settings in `options`
+    pub fn with_options(options: &TransportOptions) -> Result<Self, APIError> {
+        let mut builder = reqwest::Client::builder();
+        if options.tls.is_customised() {
+            builder = builder.use_preconfigured_tls(options.tls.client_config()?);
+        }
+        if let Some(timeout) = options.timeout_connect {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = options.timeout_read {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(max_idle_connections) = options.max_idle_connections_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle_connections);
+        }
+        Ok(Self {
+            client: builder.build()?,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncHttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        let method = match request.method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+        };
+        let mut reqwest_request = self.client.request(method, request.url);
+        for (name, value) in &request.headers {
+            reqwest_request = reqwest_request.header(name, value);
+        }
+        if let Some(body) = request.body {
+            reqwest_request = reqwest_request.body(body);
+        }
+        let response = reqwest_request.send().await?;
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        let body = response.bytes().await?.to_vec();
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// In-memory transport which returns queued responses in order and records every request.
+///
+/// Clones share the same queue and request log, so a clone can be handed to a config struct
+/// while the original is kept to queue responses and inspect requests.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryTransport {
+    responses: Arc<Mutex<VecDeque<Result<HttpResponse, String>>>>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response to be returned by the next request
+    pub fn push_response(&self, response: HttpResponse) {
+        self.responses.lock().unwrap().push_back(Ok(response));
+    }
+
+    /// Queue a `200 OK` response with `body`
+    pub fn push_body(&self, body: &str) {
+        self.push_response(HttpResponse::new(200, body));
+    }
+
+    /// Queue a transport failure, as if the server could not be reached
+    pub fn push_error(&self, message: &str) {
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back(Err(message.to_string()));
+    }
+
+    /// Get all requests sent through this transport so far
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn next_response(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        let url = request.url.to_string();
+        self.requests.lock().unwrap().push(request);
+        match self.responses.lock().unwrap().pop_front() {
+            Some(Ok(response)) => Ok(response),
+            Some(Err(message)) => Err(APIErrorKind::TransportError(message.into()).into()),
+            None => Err(APIErrorKind::TransportError(
+                format!("No response queued for {}", url).into(),
+            )
+            .into()),
+        }
+    }
+}
+
+impl HttpTransport for MemoryTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        self.next_response(request)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncHttpTransport for MemoryTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        self.next_response(request)
+    }
+}
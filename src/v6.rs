@@ -0,0 +1,1106 @@
+//! Client for the REST API of Pi-hole v6.
+//!
+//! Pi-hole v6 replaced `api.php` with a JSON API under `/api`, authenticated with a session ID
+//! obtained from `/api/auth`. [`PiHoleV6API`] covers the same ground as the v5 traits and maps
+//! results into the [`api_types`](crate::api_types) structs where possible, so code can target both generations.
+use crate::api_types::*;
+use crate::auth::SecretString;
+use crate::capabilities::{ApiGeneration, Capabilities};
+use crate::errors::{APIError, APIErrorKind};
+use crate::ftl_types::*;
+use crate::retry::RetryPolicy;
+use crate::transport::{HttpMethod, HttpRequest, HttpResponse, HttpTransport, TransportOptions};
+use crate::{send_request_for_response, v6_api_path, PiHoleAPIConfig, PiHoleAPIHost};
+use chrono::{DateTime, NaiveDateTime};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Header carrying the session ID
+const SESSION_HEADER: &str = "X-FTL-SID";
+
+/// Pi-hole v6 API Struct
+///
+/// Logs in on the first authenticated request and logs in again when the session expires.
+/// Clones share the same session.
+#[derive(Clone)]
+pub struct PiHoleV6APIConfig {
+    /// Host and transport configuration
+    config: PiHoleAPIConfig,
+
+    /// Web interface password, or an application password
+    password: SecretString,
+
+    /// Current session ID, shared between clones. `None` inside the option when no password is set.
+    session: Arc<Mutex<Option<Option<SecretString>>>>,
+}
+
+impl PiHoleV6APIConfig {
+    /// Creates a new Pi-hole v6 API instance which logs in with `password`.
+    /// `host` must begin with the protocol e.g. http:// or https://
+    pub fn new(host: String, password: String) -> Self {
+        Self::from_config(PiHoleAPIConfig::new(host), password)
+    }
+
+    /// Creates a new Pi-hole v6 API instance from an existing config.
+    /// The API is served next to the admin interface at the base path of the config,
+    /// e.g. from `/pihole/api` for `/pihole/admin/`.
+    pub fn from_config(config: PiHoleAPIConfig, password: String) -> Self {
+        Self {
+            config,
+            password: SecretString::new(password),
+            session: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Send requests through `transport` instead of the default `UreqTransport`
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.config = self.config.with_transport(transport);
+        self
+    }
+
+    /// Replace the transports with the built-in transports configured with `options`
+    pub fn with_transport_options(mut self, options: &TransportOptions) -> Result<Self, APIError> {
+        self.config = self.config.with_transport_options(options)?;
+        Ok(self)
+    }
+
+    /// Retry read-only requests according to `retry_policy`.
+    /// Requests which modify the Pi Hole are never retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.config = self.config.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Get the capabilities of the host from the versions it reports
+    pub fn detect_capabilities(&self) -> Result<Capabilities, APIError> {
+        Ok(Capabilities::from_versions(
+            ApiGeneration::V6,
+            &self.get_versions()?,
+        ))
+    }
+
+    /// Build a `method` request for the API endpoint made up of `segments`, which are percent encoded
+    fn request(
+        &self,
+        method: HttpMethod,
+        segments: &[&str],
+        params: &[(&str, &str)],
+    ) -> Result<HttpRequest, APIError> {
+        let base = format!(
+            "{}{}",
+            self.config.get_host(),
+            v6_api_path(self.config.get_base_path())
+        );
+        let invalid_url = |message: String| {
+            APIError::from(APIErrorKind::InvalidUrl(message)).with_url_context(method, &base)
+        };
+        let mut url =
+            url::Url::parse(&base).map_err(|error| invalid_url(format!("{}: {}", base, error)))?;
+        url.path_segments_mut()
+            .map_err(|_| invalid_url(base.clone()))?
+            .extend(segments);
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+        Ok(HttpRequest::new(method, url))
+    }
+
+    /// Get the current session ID, logging in if there is no session
+    fn current_session(&self) -> Result<Option<SecretString>, APIError> {
+        let mut session = self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        if session.is_none() {
+            *session = Some(self.new_session()?);
+        }
+        session
+            .clone()
+            .ok_or_else(|| APIErrorKind::LoginFailed.into())
+    }
+
+    /// Log in again unless another clone already replaced the `expired` session
+    fn replace_expired_session(
+        &self,
+        expired: &Option<SecretString>,
+    ) -> Result<Option<SecretString>, APIError> {
+        let mut session = self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        match session.as_ref() {
+            Some(current) if current != expired => Ok(current.clone()),
+            _ => {
+                let new_session = self.new_session()?;
+                *session = Some(new_session.clone());
+                Ok(new_session)
+            }
+        }
+    }
+
+    /// Log in with the password, returning the session ID
+    fn new_session(&self) -> Result<Option<SecretString>, APIError> {
+        let body = json!({ "password": self.password.expose_secret() });
+        let request = json_request(self.request(HttpMethod::Post, &["auth"], &[])?, &body);
+        let login_failed =
+            |status| APIError::from(APIErrorKind::LoginFailed).with_context(&request, Some(status));
+        let response = match send_request_for_response(
+            self.config.get_transport(),
+            request.clone(),
+            &RetryPolicy::NONE,
+        ) {
+            Err(error) if error.status() == Some(401) => return Err(login_failed(401)),
+            result => result?,
+        };
+        let auth: AuthResponse = parse_response(&request, &response)?;
+        if !auth.session.valid {
+            return Err(login_failed(response.status));
+        }
+        Ok(auth.session.sid.map(SecretString::new))
+    }
+
+    /// Send an authenticated request, logging in again once if the session has expired
+    fn send(
+        &self,
+        request: HttpRequest,
+        retry_policy: &RetryPolicy,
+    ) -> Result<HttpResponse, APIError> {
+        let session = self.current_session()?;
+        match self.send_with_session(request.clone(), &session, retry_policy) {
+            Err(error) if error.status() == Some(401) => {
+                let session = self.replace_expired_session(&session)?;
+                self.send_with_session(request, &session, retry_policy)
+            }
+            result => result,
+        }
+    }
+
+    fn send_with_session(
+        &self,
+        request: HttpRequest,
+        session: &Option<SecretString>,
+        retry_policy: &RetryPolicy,
+    ) -> Result<HttpResponse, APIError> {
+        let request = match session {
+            Some(sid) => request.with_header(SESSION_HEADER, sid.expose_secret()),
+            None => request,
+        };
+        send_request_for_response(self.config.get_transport(), request, retry_policy)
+    }
+
+    /// Send a read-only GET request, retried according to the retry policy
+    fn get<T: DeserializeOwned>(
+        &self,
+        segments: &[&str],
+        params: &[(&str, &str)],
+    ) -> Result<T, APIError> {
+        let request = self.request(HttpMethod::Get, segments, params)?;
+        let response = self.send(request.clone(), self.config.get_retry_policy())?;
+        parse_response(&request, &response)
+    }
+
+    /// Send a request which modifies the Pi Hole. These requests are never retried.
+    fn mutate(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        self.send(request, &RetryPolicy::NONE)
+    }
+}
+
+impl fmt::Debug for PiHoleV6APIConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let logged_in = self
+            .session
+            .lock()
+            .map(|session| session.is_some())
+            .unwrap_or(false);
+        f.debug_struct("PiHoleV6APIConfig")
+            .field("host", &self.config.host)
+            .field("logged_in", &logged_in)
+            .finish_non_exhaustive()
+    }
+}
+
+pub trait PiHoleV6API {
+    /// Log in, replacing the current session.
+    /// Called automatically by requests, but can be used to check the password.
+    fn login(&self) -> Result<(), APIError>;
+
+    /// End the current session
+    fn logout(&self) -> Result<(), APIError>;
+
+    /// Get statistics in a raw format
+    fn get_summary_raw(&self) -> Result<SummaryRaw, APIError>;
+
+    /// Get the detailed Pi-Hole versions for core, FTL and web interface.
+    fn get_versions(&self) -> Result<Versions, APIError>;
+
+    /// Get the top domains and ads and the number of queries for each. Limit the number of items with `count`.
+    fn get_top_items(&self, count: Option<u32>) -> Result<TopItems, APIError>;
+
+    /// Get the top clients and the number of queries for each. Limit the number of items with `count`.
+    fn get_top_clients(&self, count: Option<u32>) -> Result<TopClients, APIError>;
+
+    /// Get the top clients blocked and the number of queries for each. Limit the number of items with `count`.
+    fn get_top_clients_blocked(&self, count: Option<u32>) -> Result<TopClientsBlocked, APIError>;
+
+    /// Get the percentage of queries forwarded to each target.
+    fn get_forward_destinations(&self) -> Result<ForwardDestinations, APIError>;
+
+    /// Get the percentage of queries of each type.
+    fn get_query_types(&self) -> Result<QueryTypes, APIError>;
+
+    /// Get the most recent DNS queries. Limit the number of items with `count`.
+    fn get_all_queries(&self, count: u32) -> Result<Vec<Query>, APIError>;
+
+    /// Get the blocking status
+    fn get_status(&self) -> Result<Status, APIError>;
+
+    /// Enable blocking.
+    fn enable(&self) -> Result<Status, APIError>;
+
+    /// Disable blocking for `seconds` seconds.
+    fn disable(&self, seconds: u64) -> Result<Status, APIError>;
+
+    /// Add domains to a custom white/blacklist.
+    /// The audit list is not available on Pi-hole v6.
+    fn list_add(
+        &self,
+        domain: &str,
+        list: DomainList,
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Remove domain to a custom white/blacklist.
+    /// The audit list is not available on Pi-hole v6.
+    fn list_remove(
+        &self,
+        domain: &str,
+        list: DomainList,
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Get a list of domains on a particular custom white/blacklist.
+    /// Wildcard lists are stored as regular expressions, so the wildcard lists return the regex lists.
+    /// The audit list is not available on Pi-hole v6.
+    fn list_get_domains(&self, list: DomainList) -> Result<Vec<CustomListDomainDetails>, APIError>;
+
+    /// Get a list of custom DNS records
+    fn get_custom_dns_records(&self) -> Result<Vec<CustomDNSRecord>, APIError>;
+
+    /// Add a custom DNS record
+    fn add_custom_dns_record(
+        &self,
+        ip: &IpAddr,
+        domain: &str,
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Delete a custom DNS record
+    fn delete_custom_dns_record(
+        &self,
+        ip: &IpAddr,
+        domain: &str,
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Get a list of custom CNAME records
+    fn get_custom_cname_records(&self) -> Result<Vec<CustomCNAMERecord>, APIError>;
+
+    /// Add a custom CNAME record
+    fn add_custom_cname_record(
+        &self,
+        domain: &str,
+        target_domain: &str,
+    ) -> Result<ListModificationResponse, APIError>;
+
+    /// Delete a custom CNAME record
+    fn delete_custom_cname_record(
+        &self,
+        domain: &str,
+        target_domain: &str,
+    ) -> Result<ListModificationResponse, APIError>;
+}
+
+impl PiHoleV6API for PiHoleV6APIConfig {
+    fn login(&self) -> Result<(), APIError> {
+        let mut session = self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        *session = Some(self.new_session()?);
+        Ok(())
+    }
+
+    fn logout(&self) -> Result<(), APIError> {
+        let mut session = self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        if let Some(Some(sid)) = session.take() {
+            let request = self
+                .request(HttpMethod::Delete, &["auth"], &[])?
+                .with_header(SESSION_HEADER, sid.expose_secret());
+            match send_request_for_response(
+                self.config.get_transport(),
+                request,
+                &RetryPolicy::NONE,
+            ) {
+                // The session has already expired
+                Err(error) if matches!(error.status(), Some(401) | Some(410)) => {}
+                result => {
+                    result?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get_summary_raw(&self) -> Result<SummaryRaw, APIError> {
+        let summary: StatsSummary = self.get(&["stats", "summary"], &[])?;
+        let blocking: BlockingResponse = self.get(&["dns", "blocking"], &[])?;
+        let privacy: ConfigResponse<MiscConfig> =
+            self.get(&["config", "misc", "privacylevel"], &[])?;
+        let reply = |reply_type: &str| {
+            summary
+                .queries
+                .replies
+                .get(reply_type)
+                .copied()
+                .unwrap_or(0)
+        };
+
+        Ok(SummaryRaw {
+            domains_being_blocked: summary.gravity.domains_being_blocked.max(0) as u64,
+            dns_queries_today: summary.queries.total,
+            ads_blocked_today: summary.queries.blocked,
+            ads_percentage_today: summary.queries.percent_blocked,
+            unique_domains: summary.queries.unique_domains,
+            queries_forwarded: summary.queries.forwarded,
+            queries_cached: summary.queries.cached,
+            clients_ever_seen: summary.clients.total,
+            unique_clients: summary.clients.active,
+            dns_queries_all_types: summary.queries.total,
+            reply_nodata: reply("NODATA"),
+            reply_nxdomain: reply("NXDOMAIN"),
+            reply_cname: reply("CNAME"),
+            reply_ip: reply("IP"),
+            privacy_level: privacy.config.misc.privacylevel,
+            status: blocking.blocking,
+        })
+    }
+
+    fn get_versions(&self) -> Result<Versions, APIError> {
+        let response: VersionResponse = self.get(&["info", "version"], &[])?;
+        let version = response.version;
+        Ok(Versions {
+            core_update: version.core.update_available(),
+            web_update: version.web.update_available(),
+            ftl_update: version.ftl.update_available(),
+            core_current: version.core.local.version.clone().unwrap_or_default(),
+            web_current: version.web.local.version.clone().unwrap_or_default(),
+            ftl_current: version.ftl.local.version.clone().unwrap_or_default(),
+            core_latest: version.core.remote.version.unwrap_or_default(),
+            web_latest: version.web.remote.version.unwrap_or_default(),
+            ftl_latest: version.ftl.remote.version.unwrap_or_default(),
+            core_branch: version.core.local.branch.unwrap_or_default(),
+            web_branch: version.web.local.branch.unwrap_or_default(),
+            ftl_branch: version.ftl.local.branch.unwrap_or_default(),
+        })
+    }
+
+    fn get_top_items(&self, count: Option<u32>) -> Result<TopItems, APIError> {
+        let count = count.unwrap_or(10).to_string();
+        let top_queries: TopDomainsResponse =
+            self.get(&["stats", "top_domains"], &[("count", &count)])?;
+        let top_ads: TopDomainsResponse = self.get(
+            &["stats", "top_domains"],
+            &[("count", &count), ("blocked", "true")],
+        )?;
+        Ok(TopItems {
+            top_queries: top_queries.into_map(),
+            top_ads: top_ads.into_map(),
+        })
+    }
+
+    fn get_top_clients(&self, count: Option<u32>) -> Result<TopClients, APIError> {
+        let count = count.unwrap_or(10).to_string();
+        let response: TopClientsResponse =
+            self.get(&["stats", "top_clients"], &[("count", &count)])?;
+        Ok(TopClients {
+            top_sources: response.into_map(),
+        })
+    }
+
+    fn get_top_clients_blocked(&self, count: Option<u32>) -> Result<TopClientsBlocked, APIError> {
+        let count = count.unwrap_or(10).to_string();
+        let response: TopClientsResponse = self.get(
+            &["stats", "top_clients"],
+            &[("count", &count), ("blocked", "true")],
+        )?;
+        Ok(TopClientsBlocked {
+            top_sources_blocked: response.into_map(),
+        })
+    }
+
+    fn get_forward_destinations(&self) -> Result<ForwardDestinations, APIError> {
+        let response: UpstreamsResponse = self.get(&["stats", "upstreams"], &[])?;
+        let total = response.total_queries.max(1) as f64;
+        Ok(ForwardDestinations {
+            forward_destinations: response
+                .upstreams
+                .into_iter()
+                .map(|upstream| {
+                    let ip = upstream.ip.unwrap_or_default();
+                    let key = match upstream.name {
+                        Some(name) if !name.is_empty() => format!("{}|{}", name, ip),
+                        _ => ip,
+                    };
+                    (key, 100.0 * upstream.count as f64 / total)
+                })
+                .collect(),
+        })
+    }
+
+    fn get_query_types(&self) -> Result<QueryTypes, APIError> {
+        let response: QueryTypesResponse = self.get(&["stats", "query_types"], &[])?;
+        let total = response.types.values().sum::<u64>().max(1) as f64;
+        Ok(QueryTypes {
+            querytypes: response
+                .types
+                .into_iter()
+                .map(|(query_type, count)| (query_type, 100.0 * count as f64 / total))
+                .collect(),
+        })
+    }
+
+    fn get_all_queries(&self, count: u32) -> Result<Vec<Query>, APIError> {
+        let response: QueriesResponse =
+            self.get(&["queries"], &[("length", &count.to_string())])?;
+        response.queries.into_iter().map(Query::try_from).collect()
+    }
+
+    fn get_status(&self) -> Result<Status, APIError> {
+        let response: BlockingResponse = self.get(&["dns", "blocking"], &[])?;
+        Ok(Status {
+            status: response.blocking,
+        })
+    }
+
+    fn enable(&self) -> Result<Status, APIError> {
+        self.set_blocking(true, None)
+    }
+
+    fn disable(&self, seconds: u64) -> Result<Status, APIError> {
+        self.set_blocking(false, Some(seconds))
+    }
+
+    fn list_add(
+        &self,
+        domain: &str,
+        list: DomainList,
+    ) -> Result<ListModificationResponse, APIError> {
+        let list = DomainListPath::from_list(list)?;
+        let body = json!({ "domain": list.domain_entry(domain), "enabled": true });
+        let request = json_request(
+            self.request(
+                HttpMethod::Post,
+                &["domains", list.list_type, list.kind],
+                &[],
+            )?,
+            &body,
+        );
+        let response = self.mutate(request.clone())?;
+        let response: DomainsModificationResponse = parse_response(&request, &response)?;
+        Ok(response.into())
+    }
+
+    fn list_remove(
+        &self,
+        domain: &str,
+        list: DomainList,
+    ) -> Result<ListModificationResponse, APIError> {
+        let list = DomainListPath::from_list(list)?;
+        let entry = list.domain_entry(domain);
+        let request = self.request(
+            HttpMethod::Delete,
+            &["domains", list.list_type, list.kind, &entry],
+            &[],
+        )?;
+        self.mutate(request)?;
+        Ok(modification_success())
+    }
+
+    fn list_get_domains(&self, list: DomainList) -> Result<Vec<CustomListDomainDetails>, APIError> {
+        let list = DomainListPath::from_list(list)?;
+        let response: DomainsResponse = self.get(&["domains", list.list_type, list.kind], &[])?;
+        response
+            .domains
+            .into_iter()
+            .map(CustomListDomainDetails::try_from)
+            .collect()
+    }
+
+    fn get_custom_dns_records(&self) -> Result<Vec<CustomDNSRecord>, APIError> {
+        let response: ConfigResponse<DnsConfig> = self.get(&["config", "dns", "hosts"], &[])?;
+        response
+            .config
+            .dns
+            .hosts
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| {
+                let mut parts = entry.split_whitespace();
+                let invalid_entry = || {
+                    APIErrorKind::InvalidResponse(format!("Invalid custom DNS entry: {}", entry))
+                };
+                let ip_address = parts
+                    .next()
+                    .and_then(|ip| ip.parse().ok())
+                    .ok_or_else(invalid_entry)?;
+                let domain = parts.next().ok_or_else(invalid_entry)?.to_string();
+                Ok(CustomDNSRecord { domain, ip_address })
+            })
+            .collect()
+    }
+
+    fn add_custom_dns_record(
+        &self,
+        ip: &IpAddr,
+        domain: &str,
+    ) -> Result<ListModificationResponse, APIError> {
+        let entry = format!("{} {}", ip, domain);
+        let request = self.request(HttpMethod::Put, &["config", "dns", "hosts", &entry], &[])?;
+        self.mutate(request)?;
+        Ok(modification_success())
+    }
+
+    fn delete_custom_dns_record(
+        &self,
+        ip: &IpAddr,
+        domain: &str,
+    ) -> Result<ListModificationResponse, APIError> {
+        let entry = format!("{} {}", ip, domain);
+        let request = self.request(HttpMethod::Delete, &["config", "dns", "hosts", &entry], &[])?;
+        self.mutate(request)?;
+        Ok(modification_success())
+    }
+
+    fn get_custom_cname_records(&self) -> Result<Vec<CustomCNAMERecord>, APIError> {
+        let response: ConfigResponse<DnsConfig> =
+            self.get(&["config", "dns", "cnameRecords"], &[])?;
+        response
+            .config
+            .dns
+            .cname_records
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| {
+                let mut parts = entry.split(',');
+                match (parts.next(), parts.next()) {
+                    (Some(domain), Some(target_domain)) => Ok(CustomCNAMERecord {
+                        domain: domain.to_string(),
+                        target_domain: target_domain.to_string(),
+                    }),
+                    _ => Err(APIErrorKind::InvalidResponse(format!(
+                        "Invalid custom CNAME entry: {}",
+                        entry
+                    ))
+                    .into()),
+                }
+            })
+            .collect()
+    }
+
+    fn add_custom_cname_record(
+        &self,
+        domain: &str,
+        target_domain: &str,
+    ) -> Result<ListModificationResponse, APIError> {
+        let entry = format!("{},{}", domain, target_domain);
+        let request = self.request(
+            HttpMethod::Put,
+            &["config", "dns", "cnameRecords", &entry],
+            &[],
+        )?;
+        self.mutate(request)?;
+        Ok(modification_success())
+    }
+
+    fn delete_custom_cname_record(
+        &self,
+        domain: &str,
+        target_domain: &str,
+    ) -> Result<ListModificationResponse, APIError> {
+        let entry = format!("{},{}", domain, target_domain);
+        let request = self.request(
+            HttpMethod::Delete,
+            &["config", "dns", "cnameRecords", &entry],
+            &[],
+        )?;
+        self.mutate(request)?;
+        Ok(modification_success())
+    }
+}
+
+impl PiHoleV6APIConfig {
+    fn set_blocking(&self, blocking: bool, timer: Option<u64>) -> Result<Status, APIError> {
+        let body = json!({ "blocking": blocking, "timer": timer });
+        let request = json_request(
+            self.request(HttpMethod::Post, &["dns", "blocking"], &[])?,
+            &body,
+        );
+        let response = self.mutate(request.clone())?;
+        let response: BlockingResponse = parse_response(&request, &response)?;
+        Ok(Status {
+            status: response.blocking,
+        })
+    }
+}
+
+/// Deserialize the JSON `response` to `request`. Errors carry the endpoint of the request.
+fn parse_response<T: DeserializeOwned>(
+    request: &HttpRequest,
+    response: &HttpResponse,
+) -> Result<T, APIError> {
+    response
+        .text()
+        .and_then(|text| Ok(serde_json::from_str(&text)?))
+        .map_err(|error| error.with_context(request, Some(response.status)))
+}
+
+/// Replace the body of `request` with `body` encoded as JSON
+fn json_request(mut request: HttpRequest, body: &serde_json::Value) -> HttpRequest {
+    request.body = Some(body.to_string().into_bytes());
+    request.with_header("Content-Type", "application/json")
+}
+
+fn modification_success() -> ListModificationResponse {
+    ListModificationResponse {
+        success: true,
+        message: None,
+    }
+}
+
+/// Convert a timestamp in seconds with a fractional part to a date time
+fn timestamp_to_naive_datetime(timestamp: f64) -> Result<NaiveDateTime, APIError> {
+    let seconds = timestamp.trunc() as i64;
+    let nanoseconds = (timestamp.fract() * 1e9) as u32;
+    DateTime::from_timestamp(seconds, nanoseconds)
+        .filter(|_| timestamp.is_finite())
+        .map(|datetime| datetime.naive_utc())
+        .ok_or_else(|| {
+            APIErrorKind::InvalidResponse(format!("Invalid timestamp {}", timestamp)).into()
+        })
+}
+
+/// Endpoint of a domain list
+struct DomainListPath {
+    /// `allow` or `deny`
+    list_type: &'static str,
+    /// `exact` or `regex`
+    kind: &'static str,
+    /// Whether domains are converted to a regex matching the domain and its subdomains
+    wildcard: bool,
+}
+
+impl DomainListPath {
+    fn from_list(list: DomainList) -> Result<Self, APIError> {
+        let (list_type, kind, wildcard) = match list {
+            DomainList::ExactAllow => ("allow", "exact", false),
+            DomainList::ExactDeny => ("deny", "exact", false),
+            DomainList::RegexAllow => ("allow", "regex", false),
+            DomainList::RegexDeny => ("deny", "regex", false),
+            DomainList::WildcardAllow => ("allow", "regex", true),
+            DomainList::WildcardDeny => ("deny", "regex", true),
+            DomainList::Audit => {
+                return Err(APIErrorKind::Unsupported(
+                    "The audit list is not available on Pi-hole v6".to_string(),
+                )
+                .into())
+            }
+        };
+        Ok(Self {
+            list_type,
+            kind,
+            wildcard,
+        })
+    }
+
+    /// Get the list entry for `domain`, converting it to a regex for wildcard lists as the v5 web interface does
+    fn domain_entry(&self, domain: &str) -> String {
+        if self.wildcard {
+            format!("(\\.|^){}$", domain.replace('.', "\\."))
+        } else {
+            domain.to_string()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthResponse {
+    session: AuthSession,
+}
+
+#[derive(Deserialize)]
+struct AuthSession {
+    valid: bool,
+    sid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StatsSummary {
+    queries: QueriesSummary,
+    clients: ClientsSummary,
+    gravity: GravitySummary,
+}
+
+#[derive(Deserialize)]
+struct QueriesSummary {
+    total: u64,
+    blocked: u64,
+    percent_blocked: f64,
+    unique_domains: u64,
+    forwarded: u64,
+    cached: u64,
+    #[serde(default)]
+    replies: HashMap<String, u64>,
+}
+
+#[derive(Deserialize)]
+struct ClientsSummary {
+    active: u64,
+    total: u64,
+}
+
+#[derive(Deserialize)]
+struct GravitySummary {
+    domains_being_blocked: i64,
+}
+
+#[derive(Deserialize)]
+struct BlockingResponse {
+    blocking: String,
+}
+
+#[derive(Deserialize)]
+struct ConfigResponse<T> {
+    config: T,
+}
+
+#[derive(Deserialize)]
+struct MiscConfig {
+    misc: PrivacyLevel,
+}
+
+#[derive(Deserialize)]
+struct PrivacyLevel {
+    privacylevel: u64,
+}
+
+#[derive(Deserialize)]
+struct DnsConfig {
+    dns: DnsRecords,
+}
+
+#[derive(Deserialize)]
+struct DnsRecords {
+    hosts: Option<Vec<String>>,
+    #[serde(rename = "cnameRecords")]
+    cname_records: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: ComponentVersions,
+}
+
+#[derive(Deserialize)]
+struct ComponentVersions {
+    core: ComponentVersion,
+    web: ComponentVersion,
+    ftl: ComponentVersion,
+}
+
+#[derive(Deserialize)]
+struct ComponentVersion {
+    local: LocalVersion,
+    remote: RemoteVersion,
+}
+
+impl ComponentVersion {
+    fn update_available(&self) -> bool {
+        match (&self.local.version, &self.remote.version) {
+            (Some(local), Some(remote)) => local != remote,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LocalVersion {
+    version: Option<String>,
+    branch: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RemoteVersion {
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TopDomainsResponse {
+    domains: Vec<TopDomain>,
+}
+
+#[derive(Deserialize)]
+struct TopDomain {
+    domain: String,
+    count: u64,
+}
+
+impl TopDomainsResponse {
+    fn into_map(self) -> HashMap<String, u64> {
+        self.domains
+            .into_iter()
+            .map(|domain| (domain.domain, domain.count))
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct TopClientsResponse {
+    clients: Vec<TopClient>,
+}
+
+#[derive(Deserialize)]
+struct TopClient {
+    ip: String,
+    name: Option<String>,
+    count: u64,
+}
+
+impl TopClientsResponse {
+    /// Map clients to counts using the v5 format of "hostname|IP" or "IP"
+    fn into_map(self) -> HashMap<String, u64> {
+        self.clients
+            .into_iter()
+            .map(|client| {
+                let key = match client.name {
+                    Some(name) if !name.is_empty() => format!("{}|{}", name, client.ip),
+                    _ => client.ip,
+                };
+                (key, client.count)
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct UpstreamsResponse {
+    upstreams: Vec<Upstream>,
+    total_queries: u64,
+}
+
+#[derive(Deserialize)]
+struct Upstream {
+    ip: Option<String>,
+    name: Option<String>,
+    count: u64,
+}
+
+#[derive(Deserialize)]
+struct QueryTypesResponse {
+    types: HashMap<String, u64>,
+}
+
+#[derive(Deserialize)]
+struct QueriesResponse {
+    queries: Vec<QueryLogEntry>,
+}
+
+#[derive(Deserialize)]
+struct QueryLogEntry {
+    time: f64,
+    #[serde(rename = "type")]
+    query_type: String,
+    domain: String,
+    cname: Option<String>,
+    status: Option<String>,
+    client: QueryClient,
+    dnssec: Option<String>,
+    reply: QueryReply,
+    list_id: Option<i32>,
+    upstream: Option<String>,
+    ede: Option<QueryEde>,
+}
+
+#[derive(Deserialize)]
+struct QueryClient {
+    ip: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct QueryReply {
+    #[serde(rename = "type")]
+    reply_type: Option<String>,
+    time: f64,
+}
+
+#[derive(Deserialize)]
+struct QueryEde {
+    code: i32,
+}
+
+impl TryFrom<QueryLogEntry> for Query {
+    type Error = APIError;
+
+    fn try_from(entry: QueryLogEntry) -> Result<Self, APIError> {
+        let response_time =
+            Duration::try_from_secs_f64(entry.reply.time.max(0.0)).map_err(|_| {
+                APIErrorKind::InvalidResponse(format!("Invalid reply time {}", entry.reply.time))
+            })?;
+        Ok(Query {
+            timestring: timestamp_to_naive_datetime(entry.time)?,
+            query_type: QueryType::from(entry.query_type.as_str()),
+            domain: entry.domain,
+            client: match entry.client.name {
+                Some(name) if !name.is_empty() => name,
+                _ => entry.client.ip,
+            },
+            status: query_status(entry.status.as_deref().unwrap_or_default()),
+            dnssec_status: dnssec_status(entry.dnssec.as_deref().unwrap_or_default()),
+            reply_type: reply_type(entry.reply.reply_type.as_deref().unwrap_or_default()),
+            response_time,
+            cname_domain: entry.cname.unwrap_or_default(),
+            regex_id: entry.list_id.unwrap_or(-1),
+            upstream_destination: entry.upstream.unwrap_or_default(),
+            ede: entry.ede.map(|ede| ede.code).unwrap_or(-1).to_string(),
+        })
+    }
+}
+
+fn query_status(status: &str) -> QueryStatus {
+    match status {
+        "GRAVITY" => QueryStatus::QueryGravity,
+        "FORWARDED" => QueryStatus::QueryForwarded,
+        "CACHE" => QueryStatus::QueryCache,
+        "REGEX" => QueryStatus::QueryRegex,
+        "DENYLIST" => QueryStatus::QueryBlacklist,
+        "EXTERNAL_BLOCKED_IP" => QueryStatus::QueryExternalBlockedIp,
+        "EXTERNAL_BLOCKED_NULL" => QueryStatus::QueryExternalBlockedNull,
+        "EXTERNAL_BLOCKED_NXRA" => QueryStatus::QueryExternalBlockedNxra,
+        "GRAVITY_CNAME" => QueryStatus::QueryGravityCname,
+        "REGEX_CNAME" => QueryStatus::QueryRegexCname,
+        "DENYLIST_CNAME" => QueryStatus::QueryBlacklistCname,
+        "RETRIED" => QueryStatus::QueryRetried,
+        "RETRIED_DNSSEC" => QueryStatus::QueryRetriedDnssec,
+        "IN_PROGRESS" => QueryStatus::QueryInProgress,
+        "DBBUSY" => QueryStatus::QueryDbbusy,
+        _ => QueryStatus::QueryUnknown,
+    }
+}
+
+fn dnssec_status(status: &str) -> DNSSECStatus {
+    match status {
+        "SECURE" => DNSSECStatus::DNSSECSecure,
+        "INSECURE" => DNSSECStatus::DNSSECInsecure,
+        "BOGUS" => DNSSECStatus::DNSSECBogus,
+        "ABANDONED" => DNSSECStatus::DNSSECAbandoned,
+        _ => DNSSECStatus::DNSSECUnspecified,
+    }
+}
+
+fn reply_type(reply_type: &str) -> ReplyType {
+    match reply_type {
+        "NODATA" => ReplyType::ReplyNODATA,
+        "NXDOMAIN" => ReplyType::ReplyNXDOMAIN,
+        "CNAME" => ReplyType::ReplyCNAME,
+        "IP" => ReplyType::ReplyIP,
+        "DOMAIN" => ReplyType::ReplyDOMAIN,
+        "RRNAME" => ReplyType::ReplyRRNAME,
+        "SERVFAIL" => ReplyType::ReplySERVFAIL,
+        "REFUSED" => ReplyType::ReplyREFUSED,
+        "NOTIMP" => ReplyType::ReplyNOTIMP,
+        "OTHER" => ReplyType::ReplyOTHER,
+        "DNSSEC" => ReplyType::ReplyDNSSEC,
+        "NONE" => ReplyType::ReplyNONE,
+        "BLOB" => ReplyType::ReplyBLOB,
+        _ => ReplyType::ReplyUNKNOWN,
+    }
+}
+
+#[derive(Deserialize)]
+struct DomainsResponse {
+    domains: Vec<DomainEntry>,
+}
+
+#[derive(Deserialize)]
+struct DomainEntry {
+    id: u64,
+    domain: String,
+    #[serde(rename = "type")]
+    list_type: String,
+    kind: String,
+    comment: Option<String>,
+    groups: Vec<u64>,
+    enabled: bool,
+    date_added: i64,
+    date_modified: i64,
+}
+
+impl TryFrom<DomainEntry> for CustomListDomainDetails {
+    type Error = APIError;
+
+    fn try_from(entry: DomainEntry) -> Result<Self, APIError> {
+        // Numeric types used by the v5 database
+        let domain_type = match (entry.list_type.as_str(), entry.kind.as_str()) {
+            ("allow", "exact") => 0,
+            ("deny", "exact") => 1,
+            ("allow", "regex") => 2,
+            _ => 3,
+        };
+        Ok(CustomListDomainDetails {
+            id: entry.id,
+            domain_type,
+            domain: entry.domain,
+            enabled: entry.enabled,
+            date_added: timestamp_to_naive_datetime(entry.date_added as f64)?,
+            date_modified: timestamp_to_naive_datetime(entry.date_modified as f64)?,
+            comment: entry.comment.unwrap_or_default(),
+            groups: entry.groups,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct DomainsModificationResponse {
+    processed: Processed,
+}
+
+#[derive(Deserialize)]
+struct Processed {
+    errors: Vec<ProcessedError>,
+}
+
+#[derive(Deserialize)]
+struct ProcessedError {
+    item: String,
+    error: String,
+}
+
+impl From<DomainsModificationResponse> for ListModificationResponse {
+    fn from(response: DomainsModificationResponse) -> Self {
+        ListModificationResponse {
+            success: response.processed.errors.is_empty(),
+            message: response
+                .processed
+                .errors
+                .into_iter()
+                .next()
+                .map(|error| format!("{}: {}", error.item, error.error)),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::{datetime, key_api};
+use pi_hole_api::ftl_types::{QueryStatus, QueryType};
+use pi_hole_api::transport::MemoryTransport;
+use pi_hole_api::AuthenticatedPiHoleAPI;
+use std::time::Duration;
+
+const FROM: i64 = 1700000000;
+const UNTIL: i64 = 1700086400;
+
+#[test]
+fn database_queries_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(
+        r#"{"data": [
+            [1700000100, "AAAA", "example.com", "laptop", 2, "8.8.8.8#53"],
+            [1700000200, "TYPE65", "example.org", "192.168.0.2", 1, null]
+        ]}"#,
+    );
+
+    let queries = key_api(&transport)
+        .get_database_queries(datetime(FROM), datetime(UNTIL))
+        .unwrap();
+    assert_eq!(
+        transport.requests()[0].url.as_str(),
+        "http://pi.hole/admin/api_db.php?getAllQueries=&from=1700000000&until=1700086400&auth=key"
+    );
+    assert_eq!(queries.len(), 2);
+    assert_eq!(queries[0].timestamp, datetime(1700000100));
+    assert_eq!(queries[0].query_type, QueryType::AAAA);
+    assert_eq!(queries[0].status, QueryStatus::QueryForwarded);
+    assert_eq!(
+        queries[0].upstream_destination.as_deref(),
+        Some("8.8.8.8#53")
+    );
+    assert_eq!(
+        queries[1].query_type,
+        QueryType::Unknown("TYPE65".to_string())
+    );
+    assert_eq!(queries[1].upstream_destination, None);
+}
+
+#[test]
+fn database_top_lists_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"top_sources": {"laptop|192.168.0.2": 120}}"#);
+    transport.push_body(r#"{"top_domains": {"example.com": 80}}"#);
+    transport.push_body(r#"{"top_ads": []}"#);
+
+    let api = key_api(&transport);
+    let clients = api
+        .get_database_top_clients(datetime(FROM), datetime(UNTIL))
+        .unwrap();
+    assert_eq!(clients.top_sources["laptop|192.168.0.2"], 120);
+    let domains = api
+        .get_database_top_domains(datetime(FROM), datetime(UNTIL))
+        .unwrap();
+    assert_eq!(domains["example.com"], 80);
+    let ads = api
+        .get_database_top_ads(datetime(FROM), datetime(UNTIL))
+        .unwrap();
+    assert!(ads.is_empty());
+    assert_eq!(
+        transport.requests()[2].url.query(),
+        Some("topAds=&from=1700000000&until=1700086400&auth=key")
+    );
+}
+
+#[test]
+fn database_graph_data_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(
+        r#"{"domains_over_time": {"1700001800": 50}, "ads_over_time": {"1700001800": 5}}"#,
+    );
+
+    let data = key_api(&transport)
+        .get_database_graph_data(datetime(FROM), datetime(UNTIL), Duration::from_secs(3600))
+        .unwrap();
+    assert_eq!(data.domains_over_time["1700001800"], 50);
+    assert_eq!(data.ads_over_time["1700001800"], 5);
+    assert_eq!(
+        transport.requests()[0].url.query(),
+        Some("getGraphData=&from=1700000000&until=1700086400&interval=3600&auth=key")
+    );
+}
+
+#[test]
+fn database_info_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"filesize": 1048576}"#);
+    transport.push_body(r#"{"mintimestamp": 1690000000}"#);
+    transport.push_body(r#"{"mintimestamp": null}"#);
+
+    let api = key_api(&transport);
+    assert_eq!(api.get_database_file_size().unwrap(), 1048576);
+    assert_eq!(
+        api.get_database_min_timestamp().unwrap(),
+        Some(datetime(1690000000))
+    );
+    assert_eq!(api.get_database_min_timestamp().unwrap(), None);
+}
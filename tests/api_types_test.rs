@@ -0,0 +1,55 @@
+mod common;
+
+use common::api_with_body;
+use pi_hole_api::api_types::DomainList;
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::transport::MemoryTransport;
+use pi_hole_api::AuthenticatedPiHoleAPI;
+use strum::IntoEnumIterator;
+
+#[test]
+fn domain_list_round_trip_test() {
+    for list in DomainList::iter() {
+        assert_eq!(list.to_string().parse::<DomainList>().unwrap(), list);
+    }
+    assert_eq!(DomainList::RegexDeny.to_string(), "black_regex");
+    assert!(matches!(
+        "NOT_A_LIST".parse::<DomainList>().unwrap_err().kind(),
+        APIErrorKind::InvalidList
+    ));
+}
+
+#[test]
+fn domain_list_request_test() {
+    let transport = MemoryTransport::new();
+    let api = api_with_body(
+        &transport,
+        r#"{"success": true, "message": "Added example.com"}"#,
+    );
+
+    assert!(
+        api.list_add("example.com", DomainList::WildcardDeny)
+            .unwrap()
+            .success
+    );
+    assert_eq!(
+        transport.requests()[0].url.as_str(),
+        "http://pi.hole/admin/api.php?add=example.com&list=black_wild&auth=key"
+    );
+}
+
+#[test]
+fn list_get_domains_enabled_test() {
+    let transport = MemoryTransport::new();
+    let api = api_with_body(
+        &transport,
+        r#"{"data": [
+            {"id": 1, "type": 1, "domain": "ads.example.com", "enabled": 1, "date_added": 1611912000, "date_modified": 1611912000, "comment": "", "groups": [0]},
+            {"id": 2, "type": 1, "domain": "old.example.com", "enabled": 0, "date_added": 1611912000, "date_modified": 1611912000, "comment": "", "groups": [0]}
+        ]}"#,
+    );
+
+    let domains = api.list_get_domains(DomainList::ExactDeny).unwrap();
+    assert!(domains[0].enabled);
+    assert!(!domains[1].enabled);
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "async")]
+use pi_hole_api::api_types::DomainList;
+use pi_hole_api::transport::MemoryTransport;
+use pi_hole_api::{
+    AsyncAuthenticatedPiHoleAPI, AsyncUnauthenticatedPiHoleAPI, PiHoleAPIConfig,
+    PiHoleAPIConfigWithKey,
+};
+use serial_test::serial;
+use std::env;
+use std::sync::Arc;
+
+fn test_target_http_address() -> String {
+    env::var("PI_HOLE_API_TEST_TARGET_HTTP_ADDRESS")
+        .expect("Missing environmental var PI_HOLE_API_TEST_TARGET_HTTP_ADDRESS")
+}
+
+fn pi_hole_api_test_api_key() -> String {
+    env::var("PI_HOLE_API_TEST_API_KEY")
+        .expect("Missing environmental var PI_HOLE_API_TEST_API_KEY")
+}
+
+fn unauthenticated_api() -> PiHoleAPIConfig {
+    PiHoleAPIConfig::new(test_target_http_address())
+}
+
+fn authenticated_api() -> PiHoleAPIConfigWithKey {
+    PiHoleAPIConfigWithKey::new(test_target_http_address(), pi_hole_api_test_api_key())
+}
+
+#[tokio::test]
+#[serial]
+async fn async_get_summary_raw_test() {
+    let summary_raw = unauthenticated_api().get_summary_raw().await.unwrap();
+    assert!(
+        summary_raw.status == "enabled" || summary_raw.status == "disabled",
+        "Pi-Hole is neither enabled nor disabled"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn async_get_versions_test() {
+    let versions = unauthenticated_api().get_versions().await.unwrap();
+    assert!(!versions.core_current.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn async_get_all_queries_test() {
+    let queries = authenticated_api().get_all_queries(25).await.unwrap();
+    assert!(queries.len() <= 25);
+}
+
+#[tokio::test]
+#[serial]
+async fn async_list_add_and_remove_test() {
+    let api = authenticated_api();
+    let domain = "async-test.pi-hole-api.test";
+    let response = api.list_add(domain, DomainList::ExactAllow).await.unwrap();
+    assert!(response.success);
+    let domains = api.list_get_domains(DomainList::ExactAllow).await.unwrap();
+    assert!(domains.iter().any(|details| details.domain == domain));
+    let response = api
+        .list_remove(domain, DomainList::ExactAllow)
+        .await
+        .unwrap();
+    assert!(response.success);
+}
+
+#[tokio::test]
+async fn async_memory_transport_request_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"count": 42}"#);
+    let api = PiHoleAPIConfigWithKey::new("http://pi.hole".to_string(), "key".to_string())
+        .with_async_transport(Arc::new(transport.clone()));
+
+    let count = api.get_queries_count().await.unwrap();
+    assert_eq!(count, 42);
+    assert_eq!(transport.requests()[0].url.path(), "/admin/api_db.php");
+}
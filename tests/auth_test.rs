@@ -0,0 +1,95 @@
+use pi_hole_api::auth::{
+    api_token_from_password, read_setup_vars_api_token, ApiKeyLocation, SecretString,
+};
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::transport::{HttpMethod, MemoryTransport};
+use pi_hole_api::{AuthenticatedPiHoleAPI, PiHoleAPIConfigWithKey};
+use std::sync::Arc;
+use std::{env, fs, process};
+
+const TEST_HOST: &str = "http://pi.hole";
+const TEST_API_KEY: &str = "0123456789abcedf0123456789abcedf0123456789abcedf0123456789abcedf";
+
+#[test]
+fn api_key_redacted_from_debug_test() {
+    let api = PiHoleAPIConfigWithKey::new(TEST_HOST.to_string(), TEST_API_KEY.to_string());
+    let debug = format!("{:?}", api);
+    assert!(!debug.contains(TEST_API_KEY));
+    assert!(debug.contains("[REDACTED]"));
+
+    let secret = SecretString::new(TEST_API_KEY.to_string());
+    assert_eq!(format!("{:?}", secret), "[REDACTED]");
+    assert_eq!(secret.expose_secret(), TEST_API_KEY);
+}
+
+#[test]
+fn api_key_in_post_body_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"status": "disabled"}"#);
+    let api = PiHoleAPIConfigWithKey::new(TEST_HOST.to_string(), TEST_API_KEY.to_string())
+        .with_api_key_location(ApiKeyLocation::PostBody)
+        .with_transport(Arc::new(transport.clone()));
+
+    api.disable(10).unwrap();
+
+    let request = &transport.requests()[0];
+    assert_eq!(request.method, HttpMethod::Post);
+    assert_eq!(
+        request.url.as_str(),
+        "http://pi.hole/admin/api.php?disable=10"
+    );
+    assert_eq!(
+        request.body.as_deref(),
+        Some(format!("auth={}", TEST_API_KEY).as_bytes())
+    );
+    assert!(request.headers.contains(&(
+        "Content-Type".to_string(),
+        "application/x-www-form-urlencoded".to_string()
+    )));
+}
+
+#[test]
+fn api_token_from_password_test() {
+    let token = api_token_from_password("password");
+    assert_eq!(
+        token.expose_secret(),
+        "113459eb7bb31bddee85ade5230d6ad5d8b2fb52879e00a84ff6ae1067a210d3"
+    );
+
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"status": "enabled"}"#);
+    let api = PiHoleAPIConfigWithKey::from_password(TEST_HOST.to_string(), "password")
+        .with_transport(Arc::new(transport.clone()));
+    api.enable().unwrap();
+    assert!(transport.requests()[0]
+        .url
+        .as_str()
+        .ends_with("auth=113459eb7bb31bddee85ade5230d6ad5d8b2fb52879e00a84ff6ae1067a210d3"));
+}
+
+#[test]
+fn read_setup_vars_api_token_test() {
+    let path = env::temp_dir().join(format!("setupVars-{}.conf", process::id()));
+    fs::write(
+        &path,
+        format!(
+            "PIHOLE_INTERFACE=eth0\nWEBPASSWORD={}\nBLOCKING_ENABLED=true\n",
+            TEST_API_KEY
+        ),
+    )
+    .unwrap();
+    let token = read_setup_vars_api_token(&path);
+    let api = PiHoleAPIConfigWithKey::from_setup_vars(TEST_HOST.to_string(), &path);
+
+    fs::write(&path, "PIHOLE_INTERFACE=eth0\nWEBPASSWORD=\n").unwrap();
+    let missing_token = read_setup_vars_api_token(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(token.unwrap().expose_secret(), TEST_API_KEY);
+    assert!(api.is_ok());
+    assert!(matches!(
+        missing_token.unwrap_err().kind(),
+        APIErrorKind::MissingAPIKey
+    ));
+    assert!(read_setup_vars_api_token(&path).is_err());
+}
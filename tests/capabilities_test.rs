@@ -0,0 +1,117 @@
+mod common;
+
+use common::{key_api, unauthenticated_api};
+use pi_hole_api::capabilities::{ApiGeneration, Capabilities, ComponentVersion, Feature};
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::transport::{HttpResponse, MemoryTransport};
+use pi_hole_api::{AuthenticatedPiHoleAPI, UnauthenticatedPiHoleAPI};
+
+#[test]
+fn component_version_parse_test() {
+    assert_eq!(
+        ComponentVersion::parse("v5.18.2"),
+        Some(ComponentVersion::new(5, 18, 2))
+    );
+    assert_eq!(
+        ComponentVersion::parse("5.1"),
+        Some(ComponentVersion::new(5, 1, 0))
+    );
+    assert_eq!(ComponentVersion::parse("vDev-1a2b3c"), None);
+    assert!(ComponentVersion::new(5, 20, 0) > ComponentVersion::new(5, 3, 1));
+}
+
+#[test]
+fn detect_v6_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"https": false, "dns": true, "took": 0.01}"#);
+
+    let capabilities = unauthenticated_api(&transport)
+        .detect_capabilities()
+        .unwrap();
+    assert_eq!(capabilities, Capabilities::new(ApiGeneration::V6));
+    assert_eq!(
+        transport.requests()[0].url.as_str(),
+        "http://pi.hole/api/info/login"
+    );
+}
+
+#[test]
+fn detect_v6_with_base_path_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"https": false, "dns": true, "took": 0.01}"#);
+
+    let capabilities = unauthenticated_api(&transport)
+        .with_base_path("/pihole/admin/")
+        .unwrap()
+        .detect_capabilities()
+        .unwrap();
+    assert_eq!(capabilities, Capabilities::new(ApiGeneration::V6));
+    assert_eq!(
+        transport.requests()[0].url.as_str(),
+        "http://pi.hole/pihole/api/info/login"
+    );
+}
+
+#[test]
+fn detect_v5_test() {
+    let transport = MemoryTransport::new();
+    transport.push_response(HttpResponse::new(404, "Not Found"));
+    transport.push_body(
+        r#"{"core_update": false, "web_update": false, "FTL_update": true,
+            "core_current": "v5.1.2", "web_current": "v5.1.1", "FTL_current": "v5.19",
+            "core_latest": "v5.1.2", "web_latest": "v5.1.1", "FTL_latest": "v5.20",
+            "core_branch": "master", "web_branch": "master", "FTL_branch": "master"}"#,
+    );
+
+    let capabilities = unauthenticated_api(&transport)
+        .detect_capabilities()
+        .unwrap();
+    assert_eq!(capabilities.generation, ApiGeneration::V5);
+    assert_eq!(
+        capabilities.web_version,
+        Some(ComponentVersion::new(5, 1, 1))
+    );
+    assert!(capabilities.supports(Feature::CustomDns));
+    assert!(!capabilities.supports(Feature::CustomCname));
+    assert!(!capabilities.supports(Feature::ExtendedQueryLog));
+    assert!(!capabilities.supports(Feature::RestApi));
+}
+
+#[test]
+fn unsupported_request_refused_test() {
+    let transport = MemoryTransport::new();
+    let capabilities = Capabilities {
+        generation: ApiGeneration::V5,
+        core_version: Some(ComponentVersion::new(5, 0, 0)),
+        web_version: Some(ComponentVersion::new(5, 0, 0)),
+        ftl_version: Some(ComponentVersion::new(5, 0, 0)),
+    };
+    let api = key_api(&transport).with_capabilities(capabilities);
+
+    let error = api.get_custom_dns_records().err().unwrap();
+    match error.kind() {
+        APIErrorKind::Unsupported(message) => assert_eq!(
+            message,
+            "Using custom DNS records requires Pi-hole web interface v5.1.0 or later, found v5.0.0"
+        ),
+        kind => panic!("Unexpected error {:?}", kind),
+    }
+    assert_eq!(
+        error.context().unwrap().endpoint,
+        "http://pi.hole/admin/api.php"
+    );
+    assert!(transport.requests().is_empty());
+}
+
+#[test]
+fn php_api_refused_on_v6_test() {
+    let transport = MemoryTransport::new();
+    let api =
+        unauthenticated_api(&transport).with_capabilities(Capabilities::new(ApiGeneration::V6));
+
+    assert!(matches!(
+        api.get_version().err().unwrap().kind(),
+        APIErrorKind::Unsupported(_)
+    ));
+    assert!(transport.requests().is_empty());
+}
@@ -0,0 +1,73 @@
+//! Fixtures shared by the offline tests
+#![allow(dead_code)]
+
+use chrono::{DateTime, NaiveDateTime};
+use pi_hole_api::transport::{HttpRequest, HttpResponse, MemoryTransport};
+use pi_hole_api::v6::PiHoleV6APIConfig;
+use pi_hole_api::{PiHoleAPIConfig, PiHoleAPIConfigWithKey, PiHoleAPIConfigWithSession};
+use std::sync::Arc;
+
+pub const TEST_HOST: &str = "http://pi.hole";
+
+pub fn unauthenticated_api(transport: &MemoryTransport) -> PiHoleAPIConfig {
+    PiHoleAPIConfig::new(TEST_HOST.to_string()).with_transport(Arc::new(transport.clone()))
+}
+
+/// Config authenticated with the API key `key`
+pub fn key_api(transport: &MemoryTransport) -> PiHoleAPIConfigWithKey {
+    PiHoleAPIConfigWithKey::new(TEST_HOST.to_string(), "key".to_string())
+        .with_transport(Arc::new(transport.clone()))
+}
+
+/// Key authenticated config which answers the first request with `body`
+pub fn api_with_body(transport: &MemoryTransport, body: &str) -> PiHoleAPIConfigWithKey {
+    transport.push_body(body);
+    key_api(transport)
+}
+
+pub fn session_api(transport: &MemoryTransport) -> PiHoleAPIConfigWithSession {
+    PiHoleAPIConfigWithSession::new(TEST_HOST.to_string(), "password".to_string())
+        .with_transport(Arc::new(transport.clone()))
+}
+
+/// Queue the responses to a successful login with CSRF token `token`
+pub fn push_login(transport: &MemoryTransport, token: &str) {
+    push_session_login(transport, "session1", token);
+}
+
+/// Queue the responses to a successful login, starting session `session_id` with CSRF token `token`
+pub fn push_session_login(transport: &MemoryTransport, session_id: &str, token: &str) {
+    transport.push_response(HttpResponse::new(200, "<form></form>").with_header(
+        "Set-Cookie",
+        &format!("PHPSESSID={}; path=/; HttpOnly", session_id),
+    ));
+    transport.push_body("");
+    transport.push_body(&format!(
+        "<html><body><div id=\"token\" hidden>{}</div></body></html>",
+        token
+    ));
+}
+
+pub fn v6_api(transport: &MemoryTransport) -> PiHoleV6APIConfig {
+    PiHoleV6APIConfig::new(TEST_HOST.to_string(), "password".to_string())
+        .with_transport(Arc::new(transport.clone()))
+}
+
+pub fn push_event_stream(transport: &MemoryTransport, body: &str) {
+    transport.push_response(
+        HttpResponse::new(200, body).with_header("Content-Type", "text/event-stream"),
+    );
+}
+
+pub fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    request
+        .headers
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// UTC time of the Unix `timestamp`
+pub fn datetime(timestamp: i64) -> NaiveDateTime {
+    DateTime::from_timestamp(timestamp, 0).unwrap().naive_utc()
+}
@@ -0,0 +1,88 @@
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::transport::MemoryTransport;
+use pi_hole_api::{
+    AuthenticatedPiHoleAPI, PiHoleAPIConfig, PiHoleAPIConfigWithKey, UnauthenticatedPiHoleAPI,
+};
+use std::sync::Arc;
+
+#[test]
+fn try_new_accepts_valid_hosts_test() {
+    for host in &[
+        "http://pi.hole",
+        "http://pi.hole/",
+        "https://192.168.0.19:8443",
+        "http://[::1]",
+    ] {
+        assert!(
+            PiHoleAPIConfig::try_new(host.to_string()).is_ok(),
+            "{} should be accepted",
+            host
+        );
+    }
+}
+
+#[test]
+fn try_new_rejects_invalid_hosts_test() {
+    for host in &[
+        "pi.hole",
+        "192.168.0.19",
+        "ftp://pi.hole",
+        "http://",
+        "http://pi.hole/admin",
+        "http://pi.hole?summary",
+    ] {
+        assert!(
+            matches!(
+                PiHoleAPIConfig::try_new(host.to_string())
+                    .map(|_| ())
+                    .unwrap_err()
+                    .kind(),
+                APIErrorKind::InvalidUrl(_)
+            ),
+            "{} should be rejected",
+            host
+        );
+    }
+}
+
+#[test]
+fn base_path_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"version": 3}"#);
+    transport.push_body(r#"{"status": "enabled"}"#);
+
+    let api = PiHoleAPIConfigWithKey::try_new("https://pi.hole/".to_string(), "key".to_string())
+        .unwrap()
+        .with_base_path("pihole/admin")
+        .unwrap()
+        .with_transport(Arc::new(transport.clone()));
+    api.get_version().unwrap();
+    api.enable().unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(
+        requests[0].url.as_str(),
+        "https://pi.hole/pihole/admin/api.php?version"
+    );
+    assert_eq!(
+        requests[1].url.as_str(),
+        "https://pi.hole/pihole/admin/api.php?enable=&auth=key"
+    );
+}
+
+#[test]
+fn invalid_base_path_test() {
+    let api = PiHoleAPIConfig::try_new("http://pi.hole".to_string()).unwrap();
+    assert!(matches!(
+        api.with_base_path("/admin/?x=1").err().unwrap().kind(),
+        APIErrorKind::InvalidUrl(_)
+    ));
+}
+
+#[test]
+fn invalid_host_request_error_test() {
+    let api = PiHoleAPIConfig::new("pi.hole".to_string());
+    let error = api.get_version().err().unwrap();
+    assert!(matches!(error.kind(), APIErrorKind::InvalidUrl(_)));
+    assert_eq!(error.context().unwrap().endpoint, "pi.hole/admin/api.php");
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use common::{api_with_body, unauthenticated_api};
+use pi_hole_api::errors::{detect_response_errors, APIErrorKind};
+use pi_hole_api::transport::{HttpMethod, HttpResponse, MemoryTransport};
+use pi_hole_api::{AuthenticatedPiHoleAPI, PiHoleAPIConfigWithKey, UnauthenticatedPiHoleAPI};
+use std::error::Error;
+use std::sync::Arc;
+
+#[test]
+fn detect_response_errors_test() {
+    assert!(matches!(
+        detect_response_errors("[]").unwrap_err().kind(),
+        APIErrorKind::Unauthorized
+    ));
+    assert!(matches!(
+        detect_response_errors("Not authorized!")
+            .unwrap_err()
+            .kind(),
+        APIErrorKind::Unauthorized
+    ));
+    assert!(matches!(
+        detect_response_errors("Invalid list [not_a_list]")
+            .unwrap_err()
+            .kind(),
+        APIErrorKind::InvalidList
+    ));
+    assert!(matches!(
+        detect_response_errors(r#"{"FTLnotrunning": true}"#)
+            .unwrap_err()
+            .kind(),
+        APIErrorKind::FTLNotRunning
+    ));
+    match detect_response_errors(
+        r#"{"error": {"key": "bad_request", "message": "Invalid domain"}}"#,
+    )
+    .unwrap_err()
+    .kind()
+    {
+        APIErrorKind::ServerError(message) => assert_eq!(message, "Invalid domain"),
+        kind => panic!("Unexpected error {:?}", kind),
+    }
+    assert!(detect_response_errors(r#"{"status": "enabled"}"#).is_ok());
+    assert!(detect_response_errors(r#"{"data": []}"#).is_ok());
+}
+
+#[test]
+fn wrong_api_key_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body("[]");
+    let api = PiHoleAPIConfigWithKey::new("http://pi.hole".to_string(), "wrong".to_string())
+        .with_transport(Arc::new(transport.clone()));
+
+    let error = api.get_query_types().err().unwrap();
+    assert!(matches!(error.kind(), APIErrorKind::Unauthorized));
+    assert_eq!(error.status(), Some(200));
+}
+
+#[test]
+fn missing_api_key_test() {
+    let transport = MemoryTransport::new();
+    let api = PiHoleAPIConfigWithKey::new("http://pi.hole".to_string(), String::new())
+        .with_transport(Arc::new(transport.clone()));
+
+    let error = api.enable().err().unwrap();
+    assert!(matches!(error.kind(), APIErrorKind::MissingAPIKey));
+    assert_eq!(
+        error.to_string(),
+        "GET http://pi.hole/admin/api.php?enable&auth=[REDACTED] failed"
+    );
+    assert!(transport.requests().is_empty());
+}
+
+#[test]
+fn error_context_redacts_api_key_test() {
+    let transport = MemoryTransport::new();
+    transport.push_response(HttpResponse::new(500, "Internal Server Error"));
+    let api = PiHoleAPIConfigWithKey::new("http://pi.hole".to_string(), "secret".to_string())
+        .with_transport(Arc::new(transport.clone()));
+
+    let error = api.get_top_items(&Some(5)).err().unwrap();
+    assert!(matches!(error.kind(), APIErrorKind::HttpStatus(500)));
+    let context = error.context().unwrap();
+    assert_eq!(context.method, HttpMethod::Get);
+    assert_eq!(context.endpoint, "http://pi.hole/admin/api.php");
+    assert_eq!(context.status, Some(500));
+    assert_eq!(
+        error.to_string(),
+        "GET http://pi.hole/admin/api.php?topItems=5&auth=[REDACTED] (HTTP 500) failed"
+    );
+    assert!(!format!("{:?}", error).contains("secret"));
+    assert_eq!(
+        error.source().unwrap().to_string(),
+        "Server returned HTTP status 500"
+    );
+}
+
+#[test]
+fn error_source_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body("not json");
+    let api = unauthenticated_api(&transport);
+
+    let error = api.get_summary_raw().err().unwrap();
+    assert!(matches!(error.kind(), APIErrorKind::SerdeJSONError(_)));
+    let source = error.source().unwrap();
+    assert_eq!(source.to_string(), "Failed to parse the response");
+    assert!(source.source().unwrap().is::<serde_json::Error>());
+}
+
+#[test]
+fn missing_field_test() {
+    let error = api_with_body(&MemoryTransport::new(), r#"{"other": 1}"#)
+        .get_queries_count()
+        .err()
+        .unwrap();
+    match error.kind() {
+        APIErrorKind::MissingField(field) => assert_eq!(field, "count"),
+        kind => panic!("Unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn malformed_custom_dns_record_test() {
+    let api = api_with_body(
+        &MemoryTransport::new(),
+        r#"{"data": [["nas.lan", "not an ip"]]}"#,
+    );
+    assert!(matches!(
+        api.get_custom_dns_records().err().unwrap().kind(),
+        APIErrorKind::InvalidResponse(_)
+    ));
+
+    let api = api_with_body(&MemoryTransport::new(), r#"{"data": [["nas.lan"]]}"#);
+    assert!(matches!(
+        api.get_custom_dns_records().err().unwrap().kind(),
+        APIErrorKind::InvalidResponse(_)
+    ));
+}
+
+#[test]
+fn malformed_query_test() {
+    let api = api_with_body(
+        &MemoryTransport::new(),
+        r#"{"data": [["1656247185", "A", "example.com", "192.168.0.2", "not a status", "0", "4", "10", "", "-1", "", ""]]}"#,
+    );
+    match api.get_all_queries(1).err().unwrap().kind() {
+        APIErrorKind::SerdeJSONError(error) => assert!(error.to_string().contains("not a status")),
+        kind => panic!("Unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn overflowing_reply_time_test() {
+    let api = api_with_body(
+        &MemoryTransport::new(),
+        r#"{"data": [["1656247185", "A", "example.com", "192.168.0.2", "2", "0", "4", "184467440737095517", "", "-1", "", ""]]}"#,
+    );
+    match api.get_all_queries(1).err().unwrap().kind() {
+        APIErrorKind::SerdeJSONError(error) => {
+            assert!(error.to_string().contains("184467440737095517"))
+        }
+        kind => panic!("Unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn request_error_redacts_api_key_test() {
+    let api =
+        PiHoleAPIConfigWithKey::new("http://127.0.0.1:1".to_string(), "SECRETKEY123".to_string());
+
+    let error = api.get_cache_info().err().unwrap();
+    match error.kind() {
+        APIErrorKind::RequestError(request_error) => {
+            assert_eq!(request_error.kind(), ureq::ErrorKind::ConnectionFailed)
+        }
+        kind => panic!("Unexpected error {:?}", kind),
+    }
+    let mut messages = vec![error.to_string(), format!("{:?}", error)];
+    let mut source = error.source();
+    while let Some(error) = source {
+        messages.push(error.to_string());
+        messages.push(format!("{:?}", error));
+        source = error.source();
+    }
+    assert!(messages.len() > 2);
+    for message in messages {
+        assert!(!message.contains("SECRETKEY123"), "{}", message);
+    }
+}
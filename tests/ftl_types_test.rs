@@ -19,9 +19,11 @@ fn deserialize_dnssec_status_test() {
 }
 
 #[test]
-#[should_panic]
-fn panic_deserialize_invalid_dnssec_status_value_test() {
-    serde_json::from_str::<DNSSECStatus>(&(DNSSECStatus::COUNT + 1).to_string()).unwrap();
+fn deserialize_unknown_dnssec_status_value_test() {
+    let value = DNSSECStatus::COUNT as u8 + 1;
+    let status = serde_json::from_str::<DNSSECStatus>(&value.to_string()).unwrap();
+    assert_eq!(status, DNSSECStatus::Unknown(value));
+    assert_eq!(serde_json::to_string(&status).unwrap(), value.to_string());
 }
 
 #[test]
@@ -42,9 +44,11 @@ fn deserialize_query_status_test() {
 }
 
 #[test]
-#[should_panic]
-fn panic_deserialize_invalid_query_status_value_test() {
-    serde_json::from_str::<QueryStatus>(&(QueryStatus::COUNT + 1).to_string()).unwrap();
+fn deserialize_unknown_query_status_value_test() {
+    let value = QueryStatus::COUNT as u8 + 1;
+    let status = serde_json::from_str::<QueryStatus>(&value.to_string()).unwrap();
+    assert_eq!(status, QueryStatus::Unknown(value));
+    assert_eq!(serde_json::to_string(&status).unwrap(), value.to_string());
 }
 
 #[test]
@@ -65,7 +69,40 @@ fn deserialize_reply_type_test() {
 }
 
 #[test]
-#[should_panic]
-fn panic_deserialize_invalid_reply_type_value_test() {
-    serde_json::from_str::<ReplyType>(&(ReplyType::COUNT + 1).to_string()).unwrap();
+fn deserialize_unknown_reply_type_value_test() {
+    let value = ReplyType::COUNT as u8 + 1;
+    let status = serde_json::from_str::<ReplyType>(&value.to_string()).unwrap();
+    assert_eq!(status, ReplyType::Unknown(value));
+    assert_eq!(serde_json::to_string(&status).unwrap(), value.to_string());
+}
+
+#[test]
+fn query_type_test() {
+    for query_type in QueryType::iter() {
+        let json = serde_json::to_string(&query_type).unwrap();
+        assert_eq!(json, format!("\"{}\"", query_type));
+        assert_eq!(
+            serde_json::from_str::<QueryType>(&json).unwrap(),
+            query_type
+        );
+    }
+    assert_eq!(QueryType::iter().count(), QueryType::COUNT);
+}
+
+#[test]
+fn deserialize_unknown_query_type_test() {
+    let query_type = serde_json::from_str::<QueryType>(r#""TYPE65""#).unwrap();
+    assert_eq!(query_type, QueryType::Unknown("TYPE65".to_string()));
+    assert_eq!(serde_json::to_string(&query_type).unwrap(), r#""TYPE65""#);
+}
+
+#[test]
+fn deserialize_query_with_unknown_values_test() {
+    let query: pi_hole_api::api_types::Query = serde_json::from_str(
+        r#"["1656247185", "TYPE65", "example.com", "192.168.0.2", "99", "0", "42", "10", "", "-1", "", ""]"#,
+    )
+    .unwrap();
+    assert_eq!(query.query_type, QueryType::Unknown("TYPE65".to_string()));
+    assert_eq!(query.status, QueryStatus::Unknown(99));
+    assert_eq!(query.reply_type, ReplyType::Unknown(42));
 }
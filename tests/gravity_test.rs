@@ -0,0 +1,105 @@
+mod common;
+
+use common::{push_event_stream, push_login, session_api};
+use pi_hole_api::gravity::GravityPiHoleAPI;
+use pi_hole_api::transport::MemoryTransport;
+
+#[test]
+fn gravity_update_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    // Output is sent in chunks which may end in the middle of a line
+    push_event_stream(
+        &transport,
+        concat!(
+            "data:   \u{1b}[1;32m[i]\u{1b}[0m Neutrino emissions detected...\n",
+            "data:   [i] Target: https://example.com/hosts\n",
+            "data: \n\n",
+            "data:   [i] Status: Pending...<------  [✓] Status: Retrieval successful\n",
+            "data:   [i] Number of gravity dom\n\n",
+            "data: ains: 117311 (106785 unique domains)\n",
+            "data:   [✓] Pi-hole blocking is enabled\n\n",
+        ),
+    );
+
+    let mut update = session_api(&transport).update_gravity().unwrap();
+    let lines: Vec<String> = update.by_ref().map(Result::unwrap).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "[i] Neutrino emissions detected...",
+            "[i] Target: https://example.com/hosts",
+            "[✓] Status: Retrieval successful",
+            "[i] Number of gravity domains: 117311 (106785 unique domains)",
+            "[✓] Pi-hole blocking is enabled",
+        ]
+    );
+
+    let result = update.result().unwrap();
+    assert!(result.success);
+    assert_eq!(result.gravity_domains, Some(117311));
+    assert_eq!(result.unique_domains, Some(106785));
+    assert!(result.errors.is_empty());
+
+    let request = transport.requests().pop().unwrap();
+    assert_eq!(
+        request.url.as_str(),
+        "http://pi.hole/admin/scripts/pi-hole/php/gravity.sh.php?token=token1"
+    );
+}
+
+#[test]
+fn gravity_update_failure_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    push_event_stream(
+        &transport,
+        concat!(
+            "data:   [✗] Status: Not found\n",
+            "data:   [✗] Unable to create gravity database\n",
+            "data: \n\n",
+        ),
+    );
+
+    let result = session_api(&transport)
+        .update_gravity()
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert!(!result.success);
+    assert_eq!(result.gravity_domains, None);
+    assert_eq!(
+        result.errors,
+        vec![
+            "[✗] Status: Not found",
+            "[✗] Unable to create gravity database"
+        ]
+    );
+}
+
+#[test]
+fn gravity_update_expired_session_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body("Not authorized!");
+    push_login(&transport, "token2");
+    push_event_stream(
+        &transport,
+        "data:   [i] Number of gravity domains: 5 (5 unique domains)\n\n",
+    );
+
+    let result = session_api(&transport)
+        .update_gravity()
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert!(result.success);
+    assert_eq!(result.gravity_domains, Some(5));
+    assert!(transport
+        .requests()
+        .pop()
+        .unwrap()
+        .url
+        .as_str()
+        .ends_with("?token=token2"));
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use common::{push_login, session_api};
+use pi_hole_api::api_types::{AdlistStatus, CustomListDomainDetails};
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::groups::GroupManagementPiHoleAPI;
+use pi_hole_api::transport::{HttpRequest, MemoryTransport};
+
+/// Decoded form fields of the last request
+fn last_form(transport: &MemoryTransport) -> Vec<(String, String)> {
+    let requests: Vec<HttpRequest> = transport.requests();
+    let body = requests.last().unwrap().body.clone().unwrap_or_default();
+    url::form_urlencoded::parse(&body).into_owned().collect()
+}
+
+fn field<'a>(form: &'a [(String, String)], name: &str) -> Vec<&'a str> {
+    form.iter()
+        .filter(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+        .collect()
+}
+
+#[test]
+fn get_groups_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body(
+        r#"{"data": [
+            {"id": 0, "enabled": 1, "name": "Default", "date_added": 1611912000, "date_modified": 1611912000, "description": "The default group"},
+            {"id": 1, "enabled": 0, "name": "Kids", "date_added": 1611913000, "date_modified": 1611914000, "description": null}
+        ]}"#,
+    );
+
+    let groups = session_api(&transport).get_groups().unwrap();
+    assert_eq!(groups.len(), 2);
+    assert!(groups[0].enabled);
+    assert_eq!(groups[0].description.as_deref(), Some("The default group"));
+    assert!(!groups[1].enabled);
+    assert_eq!(groups[1].name, "Kids");
+    assert_eq!(groups[1].description, None);
+
+    let form = last_form(&transport);
+    assert_eq!(field(&form, "action"), vec!["get_groups"]);
+    assert_eq!(field(&form, "token"), vec!["token1"]);
+}
+
+#[test]
+fn add_group_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body(r#"{"success": true, "message": null}"#);
+
+    let response = session_api(&transport)
+        .add_group("Living room", Some("TV and consoles"))
+        .unwrap();
+    assert!(response.success);
+
+    let form = last_form(&transport);
+    assert_eq!(field(&form, "action"), vec!["add_group"]);
+    assert_eq!(field(&form, "name"), vec!["\"Living room\""]);
+    assert_eq!(field(&form, "desc"), vec!["TV and consoles"]);
+}
+
+#[test]
+fn malformed_group_mutation_response_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body("<html>Not JSON</html>");
+
+    let error = session_api(&transport).delete_group(3).err().unwrap();
+    assert!(matches!(error.kind(), APIErrorKind::SerdeJSONError(_)));
+    let context = error.context().unwrap();
+    assert_eq!(
+        context.endpoint,
+        "http://pi.hole/admin/scripts/pi-hole/php/groups.php"
+    );
+    assert!(context
+        .params
+        .contains(&("action".to_string(), "delete_group".to_string())));
+    assert!(context
+        .params
+        .contains(&("token".to_string(), "[REDACTED]".to_string())));
+}
+
+#[test]
+fn get_clients_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body(
+        r#"{"data": [
+            {"id": 2, "ip": "10.0.0.5", "date_added": 1611912000, "date_modified": 1611912000, "comment": null, "groups": [0, 1], "name": "laptop.lan"}
+        ]}"#,
+    );
+
+    let clients = session_api(&transport).get_clients().unwrap();
+    assert_eq!(clients[0].ip, "10.0.0.5");
+    assert_eq!(clients[0].groups, vec![0, 1]);
+    assert_eq!(clients[0].name.as_deref(), Some("laptop.lan"));
+}
+
+#[test]
+fn edit_client_groups_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body(r#"{"success": true, "message": null}"#);
+
+    session_api(&transport)
+        .edit_client(2, Some("Laptop"), &[0, 3])
+        .unwrap();
+
+    let form = last_form(&transport);
+    assert_eq!(field(&form, "action"), vec!["edit_client"]);
+    assert_eq!(field(&form, "id"), vec!["2"]);
+    assert_eq!(field(&form, "comment"), vec!["Laptop"]);
+    assert_eq!(field(&form, "groups[]"), vec!["0", "3"]);
+}
+
+#[test]
+fn adlist_management_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body(
+        r#"{"data": [
+            {"id": 1, "address": "https://example.com/hosts", "enabled": 1, "date_added": 1611912000, "date_modified": 1611912000, "comment": "Migrated", "groups": [0],
+             "date_updated": 1611999000, "number": 120000, "invalid_domains": 3, "status": 2},
+            {"id": 2, "address": "https://example.org/new", "enabled": 0, "date_added": 1611912000, "date_modified": 1611912000, "comment": null, "groups": [0]}
+        ]}"#,
+    );
+    transport.push_body(r#"{"success": true, "message": null}"#);
+    transport.push_body(r#"{"success": true, "message": null}"#);
+
+    let api = session_api(&transport);
+    let adlists = api.get_adlists().unwrap();
+    assert_eq!(adlists[0].address, "https://example.com/hosts");
+    assert!(adlists[0].enabled);
+    assert_eq!(adlists[0].number, 120000);
+    assert_eq!(adlists[0].invalid_domains, 3);
+    assert_eq!(adlists[0].status, AdlistStatus::Unchanged);
+    assert!(adlists[0].date_updated.is_some());
+    assert_eq!(adlists[1].status, AdlistStatus::NotDownloaded);
+    assert_eq!(adlists[1].date_updated, None);
+
+    api.edit_adlist(1, None, false, &[]).unwrap();
+    let form = last_form(&transport);
+    assert_eq!(field(&form, "action"), vec!["edit_adlist"]);
+    assert_eq!(field(&form, "status"), vec!["0"]);
+    assert!(field(&form, "groups[]").is_empty());
+
+    api.delete_adlist(1).unwrap();
+    let form = last_form(&transport);
+    assert_eq!(field(&form, "action"), vec!["delete_adlist"]);
+    assert_eq!(field(&form, "id"), vec!["[1]"]);
+}
+
+#[test]
+fn set_domain_groups_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body(r#"{"success": true, "message": null}"#);
+
+    let domain: CustomListDomainDetails = serde_json::from_str(
+        r#"{"id": 7, "type": 1, "domain": "ads.example.com", "enabled": 1, "date_added": 1611912000,
+            "date_modified": 1611912000, "comment": "", "groups": [0]}"#,
+    )
+    .unwrap();
+    session_api(&transport)
+        .set_domain_groups(&domain, &[1, 2])
+        .unwrap();
+
+    let form = last_form(&transport);
+    assert_eq!(field(&form, "action"), vec!["edit_domain"]);
+    assert_eq!(field(&form, "id"), vec!["7"]);
+    assert_eq!(field(&form, "type"), vec!["1"]);
+    assert_eq!(field(&form, "status"), vec!["1"]);
+    assert_eq!(field(&form, "groups[]"), vec!["1", "2"]);
+}
+
+#[test]
+fn disable_adlist_keeps_settings_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body(
+        r#"{"data": [
+            {"id": 4, "address": "https://example.com/hosts", "enabled": 1, "date_added": 1611912000, "date_modified": 1611912000,
+             "comment": "Ads", "groups": [0, 2], "date_updated": null, "number": 0, "invalid_domains": 0, "status": 7}
+        ]}"#,
+    );
+    transport.push_body(r#"{"success": true, "message": null}"#);
+
+    let api = session_api(&transport);
+    let adlist = api.get_adlists().unwrap().remove(0);
+    assert_eq!(adlist.status, AdlistStatus::Unknown(7));
+    api.disable_adlist(&adlist).unwrap();
+
+    let form = last_form(&transport);
+    assert_eq!(field(&form, "action"), vec!["edit_adlist"]);
+    assert_eq!(field(&form, "id"), vec!["4"]);
+    assert_eq!(field(&form, "comment"), vec!["Ads"]);
+    assert_eq!(field(&form, "status"), vec!["0"]);
+    assert_eq!(field(&form, "groups[]"), vec!["0", "2"]);
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::{api_with_body, datetime};
+use pi_hole_api::transport::MemoryTransport;
+use pi_hole_api::AuthenticatedPiHoleAPI;
+
+#[test]
+fn over_time_data_forwards_test() {
+    let transport = MemoryTransport::new();
+    let api = api_with_body(
+        &transport,
+        r#"{
+            "over_time": {"1700000600": [60.5, 39.5], "1700000000": [50, 50]},
+            "forward_destinations": {"blocked|blocked": 10.0, "dns.google|8.8.8.8": 60.0, "cache|cache": 30.0}
+        }"#,
+    );
+
+    let data = api.get_over_time_data_forwards().unwrap();
+    let times: Vec<_> = data.over_time.keys().copied().collect();
+    assert_eq!(times, vec![datetime(1700000000), datetime(1700000600)]);
+    assert_eq!(data.over_time[&datetime(1700000600)], vec![60.5, 39.5]);
+    let names: Vec<_> = data
+        .forward_destinations
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["blocked|blocked", "dns.google|8.8.8.8", "cache|cache"]
+    );
+    assert_eq!(
+        transport.requests()[0].url.query(),
+        Some("overTimeDataForwards=&auth=key")
+    );
+}
+
+#[test]
+fn over_time_data_query_types_test() {
+    let transport = MemoryTransport::new();
+    let api = api_with_body(&transport, r#"{"over_time": []}"#);
+    assert!(api
+        .get_over_time_data_query_types()
+        .unwrap()
+        .over_time
+        .is_empty());
+
+    transport.push_body(r#"{"over_time": {"1700000000": [75.0, 25.0]}}"#);
+    let data = api.get_over_time_data_query_types().unwrap();
+    assert_eq!(data.over_time[&datetime(1700000000)], vec![75.0, 25.0]);
+}
+
+#[test]
+fn forward_destination_names_test() {
+    let transport = MemoryTransport::new();
+    let api = api_with_body(
+        &transport,
+        r#"{"forward_destinations": {"dns.google|8.8.8.8": 70.5, "one.one.one.one|1.1.1.1": 29.5}}"#,
+    );
+
+    let names = api.get_forward_destination_names().unwrap();
+    assert_eq!(
+        names.forward_destinations,
+        vec![
+            ("dns.google|8.8.8.8".to_string(), 70.5),
+            ("one.one.one.one|1.1.1.1".to_string(), 29.5)
+        ]
+    );
+}
+
+#[test]
+fn recent_blocked_test() {
+    let transport = MemoryTransport::new();
+    let api = api_with_body(&transport, "ads.example.com\ntracker.example.org\n");
+
+    let domains = api.get_recent_blocked(Some(2)).unwrap();
+    assert_eq!(domains, vec!["ads.example.com", "tracker.example.org"]);
+    assert_eq!(
+        transport.requests()[0].url.query(),
+        Some("recentBlocked=2&auth=key")
+    );
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::{datetime, key_api};
+use pi_hole_api::ftl_types::QueryType;
+use pi_hole_api::query_log::QueryFilter;
+use pi_hole_api::transport::MemoryTransport;
+use pi_hole_api::AuthenticatedPiHoleAPI;
+
+/// Query log row of `client` querying `domain`, with status `status`
+fn row(timestamp: i64, query_type: &str, domain: &str, client: &str, status: u8) -> String {
+    format!(
+        r#"["{}", "{}", "{}", "{}", "{}", "0", "4", "10", "", "-1", "8.8.8.8#53", ""]"#,
+        timestamp, query_type, domain, client, status
+    )
+}
+
+fn push_rows(transport: &MemoryTransport, rows: &[String]) {
+    transport.push_body(&format!(r#"{{"data": [{}]}}"#, rows.join(",")));
+}
+
+#[test]
+fn query_filter_domain_and_client_test() {
+    let transport = MemoryTransport::new();
+    push_rows(
+        &transport,
+        &[
+            row(1700000000, "A", "example.com", "laptop", 2),
+            row(1700000001, "A", "example.com", "phone", 2),
+        ],
+    );
+
+    let filter = QueryFilter::new()
+        .with_domain("example.com")
+        .with_client("phone");
+    let queries = key_api(&transport).get_queries(&filter).unwrap();
+    assert_eq!(queries.len(), 1);
+    assert_eq!(queries[0].client, "phone");
+    assert_eq!(
+        transport.requests()[0].url.as_str(),
+        "http://pi.hole/admin/api.php?getAllQueries=&domain=example.com&auth=key"
+    );
+}
+
+#[test]
+fn query_filter_time_range_test() {
+    let transport = MemoryTransport::new();
+    push_rows(
+        &transport,
+        &[
+            row(1700000000, "A", "example.com", "laptop", 2),
+            row(1700000060, "AAAA", "example.com", "laptop", 1),
+            row(1700000120, "AAAA", "example.org", "laptop", 1),
+        ],
+    );
+
+    let from = datetime(1700000000);
+    let until = datetime(1700003600);
+    let filter = QueryFilter::new()
+        .with_time_range(from, until)
+        .with_query_type(QueryType::AAAA)
+        .with_blocked_only()
+        .with_count(1);
+    let queries = key_api(&transport).get_queries(&filter).unwrap();
+    assert_eq!(queries.len(), 1);
+    assert_eq!(queries[0].domain, "example.org");
+    assert_eq!(
+        transport.requests()[0].url.as_str(),
+        "http://pi.hole/admin/api.php?getAllQueries=&from=1700000000&until=1700003600&auth=key"
+    );
+}
+
+#[test]
+fn query_filter_count_after_local_filter_test() {
+    let transport = MemoryTransport::new();
+    push_rows(
+        &transport,
+        &[
+            row(1700000000, "A", "ads.example.com", "laptop", 1),
+            row(1700000001, "A", "example.com", "laptop", 2),
+            row(1700000002, "A", "tracker.example.com", "laptop", 1),
+            row(1700000003, "A", "example.org", "laptop", 2),
+        ],
+    );
+    push_rows(&transport, &[]);
+
+    let api = key_api(&transport);
+    let filter = QueryFilter::new().with_blocked_only().with_count(2);
+    let queries = api.get_queries(&filter).unwrap();
+    let domains: Vec<&str> = queries.iter().map(|query| query.domain.as_str()).collect();
+    assert_eq!(domains, vec!["ads.example.com", "tracker.example.com"]);
+    api.get_queries(&QueryFilter::new().with_count(2)).unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].url.query(), Some("getAllQueries=&auth=key"));
+    assert_eq!(requests[1].url.query(), Some("getAllQueries=2&auth=key"));
+}
+
+#[test]
+fn query_filter_server_side_test() {
+    let transport = MemoryTransport::new();
+    push_rows(&transport, &[]);
+    push_rows(&transport, &[]);
+    push_rows(&transport, &[]);
+
+    let api = key_api(&transport);
+    let filter = QueryFilter::new()
+        .with_client("192.168.0.2")
+        .with_blocked_only();
+    api.get_queries(&filter).unwrap();
+    let filter = QueryFilter::new().with_query_type(QueryType::AAAA);
+    api.get_queries(&filter).unwrap();
+    let filter = QueryFilter::new().with_forward_destination("8.8.8.8");
+    api.get_queries(&filter).unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(
+        requests[0].url.query(),
+        Some("getAllQueries=&client=192.168.0.2&type=blocked&auth=key")
+    );
+    assert_eq!(
+        requests[1].url.query(),
+        Some("getAllQueries=&querytype=2&auth=key")
+    );
+    assert_eq!(
+        requests[2].url.query(),
+        Some("getAllQueries=&forwarddest=8.8.8.8&auth=key")
+    );
+}
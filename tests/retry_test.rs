@@ -0,0 +1,129 @@
+mod common;
+
+use common::key_api;
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::retry::RetryPolicy;
+use pi_hole_api::transport::{HttpResponse, MemoryTransport};
+use pi_hole_api::{AuthenticatedPiHoleAPI, PiHoleAPIConfigWithKey, UnauthenticatedPiHoleAPI};
+use std::time::Duration;
+
+fn api_with_retries(transport: &MemoryTransport, max_retries: u32) -> PiHoleAPIConfigWithKey {
+    key_api(transport).with_retry_policy(
+        RetryPolicy::new(max_retries).with_backoff(Duration::ZERO, Duration::ZERO),
+    )
+}
+
+#[test]
+fn retry_read_request_test() {
+    let transport = MemoryTransport::new();
+    transport.push_error("connection reset");
+    transport.push_response(HttpResponse::new(503, "Service Unavailable"));
+    transport.push_body(r#"{"version": 3}"#);
+
+    let version = api_with_retries(&transport, 2).get_version().unwrap();
+    assert_eq!(version, 3);
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[test]
+fn retry_gives_up_after_max_retries_test() {
+    let transport = MemoryTransport::new();
+    for _ in 0..3 {
+        transport.push_response(HttpResponse::new(502, "Bad Gateway"));
+    }
+
+    let response = api_with_retries(&transport, 2).get_all_queries(10);
+    assert!(matches!(
+        response.err().unwrap().kind(),
+        APIErrorKind::HttpStatus(502)
+    ));
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[test]
+fn retry_skips_mutations_test() {
+    let transport = MemoryTransport::new();
+    transport.push_error("connection reset");
+    transport.push_body(r#"{"status": "disabled"}"#);
+
+    let response = api_with_retries(&transport, 2).disable(10);
+    assert!(matches!(
+        response.err().unwrap().kind(),
+        APIErrorKind::TransportError(_)
+    ));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn retry_skips_permanent_errors_test() {
+    let transport = MemoryTransport::new();
+    transport.push_response(HttpResponse::new(404, "Not Found"));
+    transport.push_body(r#"{"version": 3}"#);
+
+    let response = api_with_retries(&transport, 2).get_version();
+    assert!(matches!(
+        response.err().unwrap().kind(),
+        APIErrorKind::HttpStatus(404)
+    ));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn retry_backoff_test() {
+    let policy = RetryPolicy::new(5)
+        .with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+        .with_multiplier(2.0)
+        .with_jitter(0.5);
+    assert_eq!(policy.backoff(0), Duration::from_millis(100));
+    assert_eq!(policy.backoff(1), Duration::from_millis(200));
+    assert_eq!(policy.backoff(2), Duration::from_millis(400));
+    assert_eq!(policy.backoff(3), Duration::from_millis(500));
+
+    for _ in 0..100 {
+        let backoff = policy.backoff_with_jitter(1);
+        assert!(backoff >= Duration::from_millis(100) && backoff <= Duration::from_millis(300));
+    }
+}
+
+#[test]
+fn retry_backoff_invalid_multiplier_test() {
+    let policy = RetryPolicy::new(5)
+        .with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+        .with_multiplier(-2.0);
+    assert_eq!(policy.multiplier, 0.0);
+    assert_eq!(policy.backoff(0), Duration::from_millis(100));
+    assert_eq!(policy.backoff(1), Duration::ZERO);
+
+    let policy = RetryPolicy {
+        multiplier: -2.0,
+        ..policy
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(500));
+
+    let policy = RetryPolicy {
+        multiplier: f64::NAN,
+        ..policy
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(500));
+}
+
+#[test]
+fn retry_backoff_with_jitter_out_of_range_test() {
+    let policy = RetryPolicy::new(100).with_backoff(Duration::from_secs(1), Duration::MAX);
+    assert_eq!(policy.backoff(70), Duration::MAX);
+    assert!(policy.backoff_with_jitter(70) > Duration::from_secs(u64::MAX / 2));
+
+    let policy = RetryPolicy {
+        jitter: 1e300,
+        ..RetryPolicy::new(5).with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+    };
+    for _ in 0..100 {
+        assert!(policy.backoff_with_jitter(1) <= Duration::from_millis(400));
+    }
+
+    let policy = RetryPolicy {
+        jitter: f64::NAN,
+        ..policy
+    };
+    assert_eq!(policy.backoff_with_jitter(1), Duration::from_millis(500));
+}
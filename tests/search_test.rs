@@ -0,0 +1,110 @@
+mod common;
+
+use common::{push_event_stream, push_login, session_api};
+use pi_hole_api::api_types::DomainList;
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::search::{ListMatch, ListMatchSource, ListSearchPiHoleAPI, SearchMode};
+use pi_hole_api::transport::MemoryTransport;
+
+fn list_match(source: ListMatchSource, entry: &str) -> ListMatch {
+    ListMatch {
+        source,
+        entry: entry.to_string(),
+    }
+}
+
+#[test]
+fn search_lists_partial_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    push_event_stream(
+        &transport,
+        concat!(
+            "data:  Match found in \u{1b}[1mexact blacklist\u{1b}[0m\n",
+            "data:    ads.example.com\n",
+            "data:  Match found in regex blacklist\n",
+            "data:    ^ads?\\.\n",
+            "data:    (\\.|^)example\\.com$\n",
+            "data: \n\n",
+            "data:  Match found in \u{1b}[1mhttps://example.com/hosts\u{1b}[0m:\n",
+            "data:    ads.exam\n\n",
+            "data: ple.com\n",
+            "data:    tracker.ads.example.com\n",
+            "data:  [i] Over 100 results found for example.com\n",
+            "data:         This can be overridden using the -all option\n\n",
+        ),
+    );
+
+    let matches = session_api(&transport)
+        .search_lists("example.com", SearchMode::Partial)
+        .unwrap();
+    assert_eq!(
+        matches,
+        vec![
+            list_match(
+                ListMatchSource::List(DomainList::ExactDeny),
+                "ads.example.com"
+            ),
+            list_match(ListMatchSource::List(DomainList::RegexDeny), "^ads?\\."),
+            list_match(
+                ListMatchSource::List(DomainList::WildcardDeny),
+                "(\\.|^)example\\.com$"
+            ),
+            list_match(
+                ListMatchSource::Adlist("https://example.com/hosts".to_string()),
+                "ads.example.com"
+            ),
+            list_match(
+                ListMatchSource::Adlist("https://example.com/hosts".to_string()),
+                "tracker.ads.example.com"
+            ),
+        ]
+    );
+
+    let request = transport.requests().pop().unwrap();
+    assert_eq!(
+        request.url.as_str(),
+        "http://pi.hole/admin/scripts/pi-hole/php/queryads.php?domain=example.com&token=token1"
+    );
+}
+
+#[test]
+fn search_lists_exact_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    push_event_stream(
+        &transport,
+        "data:  Exact match found in exact whitelist\ndata:    example.com\n\n",
+    );
+
+    let matches = session_api(&transport)
+        .search_lists("example.com", SearchMode::Exact)
+        .unwrap();
+    assert_eq!(
+        matches,
+        vec![list_match(
+            ListMatchSource::List(DomainList::ExactAllow),
+            "example.com"
+        )]
+    );
+
+    let request = transport.requests().pop().unwrap();
+    assert_eq!(
+        request.url.query(),
+        Some("domain=example.com&exact=&token=token1")
+    );
+}
+
+#[test]
+fn search_lists_invalid_domain_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body("Invalid domain!");
+
+    let error = session_api(&transport)
+        .search_lists("not a domain", SearchMode::Partial)
+        .unwrap_err();
+    assert!(
+        matches!(error.kind(), APIErrorKind::ServerError(message) if message == "Invalid domain!")
+    );
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::{header, push_session_login, session_api};
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::transport::{HttpMethod, HttpRequest, HttpResponse, MemoryTransport};
+
+const GROUPS_PATH: &str = "scripts/pi-hole/php/groups.php";
+
+fn body(request: &HttpRequest) -> String {
+    String::from_utf8(request.body.clone().unwrap_or_default()).unwrap()
+}
+
+#[test]
+fn session_login_and_request_test() {
+    let transport = MemoryTransport::new();
+    push_session_login(&transport, "session1", "token1");
+    transport.push_body(r#"{"data": []}"#);
+
+    let response = session_api(&transport)
+        .post_form(GROUPS_PATH, &[("action", "get_groups")])
+        .unwrap();
+    assert_eq!(response, r#"{"data": []}"#);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[0].method, HttpMethod::Get);
+    assert_eq!(requests[0].url.as_str(), "http://pi.hole/admin/login.php");
+    assert_eq!(requests[1].method, HttpMethod::Post);
+    assert_eq!(body(&requests[1]), "pw=password");
+    assert_eq!(header(&requests[1], "Cookie"), Some("PHPSESSID=session1"));
+    assert_eq!(requests[2].url.as_str(), "http://pi.hole/admin/index.php");
+    assert_eq!(
+        requests[3].url.as_str(),
+        "http://pi.hole/admin/scripts/pi-hole/php/groups.php"
+    );
+    assert_eq!(body(&requests[3]), "action=get_groups&token=token1");
+    assert_eq!(header(&requests[3], "Cookie"), Some("PHPSESSID=session1"));
+}
+
+#[test]
+fn session_reused_between_requests_test() {
+    let transport = MemoryTransport::new();
+    push_session_login(&transport, "session1", "token1");
+    transport.push_body("first");
+    transport.push_body("second");
+
+    let api = session_api(&transport);
+    assert_eq!(api.post_form(GROUPS_PATH, &[]).unwrap(), "first");
+    assert_eq!(api.clone().post_form(GROUPS_PATH, &[]).unwrap(), "second");
+    assert_eq!(transport.requests().len(), 5);
+}
+
+#[test]
+fn session_expired_relogin_test() {
+    let transport = MemoryTransport::new();
+    push_session_login(&transport, "session1", "token1");
+    transport.push_body("Session expired! Please re-login on the Pi-hole dashboard.");
+    push_session_login(&transport, "session2", "token2");
+    transport.push_body("ok");
+
+    let response = session_api(&transport).post_form(GROUPS_PATH, &[]).unwrap();
+    assert_eq!(response, "ok");
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 8);
+    let retried_request = &requests[7];
+    assert_eq!(body(retried_request), "token=token2");
+    assert_eq!(
+        header(retried_request, "Cookie"),
+        Some("PHPSESSID=session2")
+    );
+}
+
+#[test]
+fn session_wrong_password_test() {
+    let transport = MemoryTransport::new();
+    transport.push_response(
+        HttpResponse::new(200, "<form></form>").with_header("Set-Cookie", "PHPSESSID=session1"),
+    );
+    transport.push_body("");
+    transport.push_body("<html><body>Wrong password!</body></html>");
+
+    let result = session_api(&transport).login();
+    assert!(matches!(
+        result.err().unwrap().kind(),
+        APIErrorKind::LoginFailed
+    ));
+}
+
+#[test]
+fn session_debug_redacted_test() {
+    let transport = MemoryTransport::new();
+    let debug = format!("{:?}", session_api(&transport));
+    assert!(!debug.contains("password"));
+    assert!(debug.contains("logged_in: false"));
+}
@@ -0,0 +1,131 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use pi_hole_api::api_types::AdlistStatus;
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::teleporter::TeleporterArchive;
+use pi_hole_api::teleporter_backup::TeleporterBackup;
+use std::net::IpAddr;
+
+/// Build a teleporter archive from `(path, contents)` pairs
+fn archive(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (path, contents) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, path, contents.as_bytes())
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+#[test]
+fn parse_teleporter_backup_test() {
+    let data = archive(&[
+        (
+            "blacklist.exact.json",
+            r#"[{"id": 3, "type": 1, "domain": "ads.example.com", "enabled": 1, "date_added": 1611912000, "date_modified": 1611912000, "comment": null}]"#,
+        ),
+        (
+            "whitelist.regex.json",
+            r#"[{"id": 4, "type": 2, "domain": "(\\.|^)example\\.org$", "enabled": 0, "date_added": 1611912000, "date_modified": 1611912000, "comment": "Wildcard"}]"#,
+        ),
+        (
+            "domainlist_by_group.json",
+            r#"[{"domainlist_id": 3, "group_id": 2}, {"domainlist_id": 3, "group_id": 0}]"#,
+        ),
+        (
+            "adlist.json",
+            r#"[{"id": 1, "address": "https://example.com/hosts", "enabled": 1, "date_added": 1611912000, "date_modified": 1611912000, "comment": "Migrated",
+                 "date_updated": 1611999000, "number": 120000, "invalid_domains": 0, "status": 1}]"#,
+        ),
+        (
+            "adlist_by_group.json",
+            r#"[{"adlist_id": 1, "group_id": 0}]"#,
+        ),
+        (
+            "group.json",
+            r#"[{"id": 0, "enabled": 1, "name": "Default", "date_added": 1611912000, "date_modified": 1611912000, "description": "The default group"}]"#,
+        ),
+        (
+            "client.json",
+            r#"[{"id": 1, "ip": "10.0.0.5", "date_added": 1611912000, "date_modified": 1611912000, "comment": null}]"#,
+        ),
+        (
+            "domain_audit.json",
+            r#"[{"id": 1, "domain": "cdn.example.com", "date_added": 1611912000}]"#,
+        ),
+        (
+            "etc/pihole/custom.list",
+            "10.0.0.2 nas.lan\n# comment\n\n10.0.0.3 printer.lan\n",
+        ),
+        (
+            "etc/dnsmasq.d/05-pihole-custom-cname.conf",
+            "cname=files.lan,nas.lan\n",
+        ),
+        (
+            "etc/dnsmasq.d/04-pihole-static-dhcp.conf",
+            "dhcp-host=AA:BB:CC:DD:EE:FF,10.0.0.2,nas\ndhcp-host=11:22:33:44:55:66,,laptop\n",
+        ),
+        (
+            "etc/pihole/setupVars.conf",
+            "PIHOLE_INTERFACE=eth0\nBLOCKING_ENABLED=true\n",
+        ),
+    ]);
+
+    let backup =
+        TeleporterBackup::from_archive(&TeleporterArchive::new("backup.tar.gz", data)).unwrap();
+    assert_eq!(backup.blacklist[0].domain, "ads.example.com");
+    assert_eq!(backup.blacklist[0].comment, "");
+    assert_eq!(backup.blacklist[0].groups, vec![0, 2]);
+    assert!(!backup.regex_whitelist[0].enabled);
+    assert!(backup.regex_whitelist[0].groups.is_empty());
+    assert!(backup.whitelist.is_empty());
+
+    assert_eq!(backup.adlists[0].status, AdlistStatus::Updated);
+    assert_eq!(backup.adlists[0].groups, vec![0]);
+    assert_eq!(backup.groups[0].name, "Default");
+    assert_eq!(backup.clients[0].ip, "10.0.0.5");
+    assert!(backup.clients[0].groups.is_empty());
+    assert_eq!(backup.audit_log, vec!["cdn.example.com"]);
+
+    assert_eq!(backup.custom_dns_records.len(), 2);
+    assert_eq!(backup.custom_dns_records[1].domain, "printer.lan");
+    assert_eq!(
+        backup.custom_dns_records[1].ip_address,
+        "10.0.0.3".parse::<IpAddr>().unwrap()
+    );
+    assert_eq!(backup.custom_cname_records[0].domain, "files.lan");
+    assert_eq!(backup.custom_cname_records[0].target_domain, "nas.lan");
+
+    let leases = &backup.static_dhcp_leases;
+    assert_eq!(leases[0].mac_address, "AA:BB:CC:DD:EE:FF");
+    assert_eq!(leases[0].ip_address, Some("10.0.0.2".parse().unwrap()));
+    assert_eq!(leases[0].hostname.as_deref(), Some("nas"));
+    assert_eq!(leases[1].ip_address, None);
+    assert_eq!(leases[1].hostname.as_deref(), Some("laptop"));
+
+    assert_eq!(backup.setup_vars["PIHOLE_INTERFACE"], "eth0");
+    assert!(backup.ftl_config.is_empty());
+}
+
+#[test]
+fn parse_invalid_teleporter_backup_test() {
+    assert!(matches!(
+        TeleporterBackup::parse(b"not an archive")
+            .err()
+            .unwrap()
+            .kind(),
+        APIErrorKind::InvalidResponse(_)
+    ));
+
+    let data = archive(&[("group.json", "{")]);
+    match TeleporterBackup::parse(&data).err().unwrap().kind() {
+        APIErrorKind::InvalidResponse(message) => {
+            assert!(message.starts_with("Invalid group.json in teleporter archive"))
+        }
+        kind => panic!("Unexpected error {:?}", kind),
+    }
+}
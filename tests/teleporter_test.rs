@@ -0,0 +1,120 @@
+mod common;
+
+use common::{header, push_login, session_api};
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::teleporter::{
+    TeleporterArchive, TeleporterImport, TeleporterPart, TeleporterPiHoleAPI,
+};
+use pi_hole_api::transport::{HttpResponse, MemoryTransport};
+
+/// Start of a gzip file followed by some data
+const ARCHIVE_DATA: [u8; 6] = [0x1f, 0x8b, 0x08, 0x00, 0xff, 0x00];
+
+#[test]
+fn export_teleporter_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_response(
+        HttpResponse::new(200, ARCHIVE_DATA.to_vec())
+            .with_header("Content-Type", "application/gzip")
+            .with_header(
+                "Content-Disposition",
+                "attachment; filename=\"pi-hole-raspberrypi-teleporter_2022-06-01_12-00-00.tar.gz\"",
+            ),
+    );
+
+    let archive = session_api(&transport).export_teleporter().unwrap();
+    assert_eq!(
+        archive.file_name,
+        "pi-hole-raspberrypi-teleporter_2022-06-01_12-00-00.tar.gz"
+    );
+    assert_eq!(archive.data, ARCHIVE_DATA.to_vec());
+
+    let request = transport.requests().pop().unwrap();
+    assert_eq!(
+        request.url.as_str(),
+        "http://pi.hole/admin/scripts/pi-hole/php/teleporter.php"
+    );
+    assert_eq!(request.body, Some(b"token=token1".to_vec()));
+    assert_eq!(header(&request, "Cookie"), Some("PHPSESSID=session1"));
+}
+
+#[test]
+fn export_teleporter_expired_session_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body("Wrong token! Please re-login on the Pi-hole dashboard.");
+    push_login(&transport, "token2");
+    transport.push_response(HttpResponse::new(200, ARCHIVE_DATA.to_vec()));
+
+    let archive = session_api(&transport).export_teleporter().unwrap();
+    assert_eq!(archive.file_name, "pi-hole-teleporter.tar.gz");
+    assert_eq!(
+        transport.requests().pop().unwrap().body,
+        Some(b"token=token2".to_vec())
+    );
+}
+
+#[test]
+fn import_teleporter_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body("Processed adlist (3 entries)<br>\nProcessed client (1 entry)<br>\nOK");
+
+    let archive = TeleporterArchive::new("backup.tar.gz", ARCHIVE_DATA.to_vec());
+    let import = TeleporterImport::new()
+        .with_part(TeleporterPart::Adlists)
+        .with_part(TeleporterPart::Clients)
+        .with_flush_tables();
+    let messages = session_api(&transport)
+        .import_teleporter(&archive, &import)
+        .unwrap();
+    assert_eq!(
+        messages,
+        vec!["Processed adlist (3 entries)", "Processed client (1 entry)"]
+    );
+
+    let request = transport.requests().pop().unwrap();
+    let content_type = header(&request, "Content-Type").unwrap();
+    let boundary = content_type
+        .strip_prefix("multipart/form-data; boundary=")
+        .unwrap();
+    let body = request.body.clone().unwrap();
+    let field = |name: &str, value: &str| {
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            boundary, name, value
+        )
+    };
+    let text = String::from_utf8_lossy(&body);
+    assert!(text.contains(&field("token", "token1")));
+    assert!(text.contains(&field("action", "in")));
+    assert!(text.contains(&field("adlist", "true")));
+    assert!(text.contains(&field("client", "true")));
+    assert!(text.contains(&field("flushtables", "true")));
+    assert!(!text.contains("name=\"whitelist\""));
+    assert!(text.contains("name=\"zip_file\"; filename=\"backup.tar.gz\""));
+    assert!(body
+        .windows(ARCHIVE_DATA.len())
+        .any(|window| window == ARCHIVE_DATA));
+    assert!(text.ends_with(&format!("--{}--\r\n", boundary)));
+}
+
+#[test]
+fn import_teleporter_failure_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "token1");
+    transport.push_body("Invalid file type");
+
+    let archive = TeleporterArchive::new("backup.zip", b"PK".to_vec());
+    let error = session_api(&transport)
+        .import_teleporter(&archive, &TeleporterImport::all().with_lists())
+        .unwrap_err();
+    assert!(
+        matches!(error.kind(), APIErrorKind::ServerError(message) if message == "Invalid file type")
+    );
+    assert_eq!(
+        error.context().unwrap().endpoint,
+        "http://pi.hole/admin/scripts/pi-hole/php/teleporter.php"
+    );
+}
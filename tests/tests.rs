@@ -1,6 +1,8 @@
+// Lints introduced after these tests were written
+#![allow(clippy::len_zero, clippy::single_component_path_imports, deprecated)]
 use chrono::prelude::*;
 use pi_hole_api;
-use pi_hole_api::errors::APIError;
+use pi_hole_api::api_types::DomainList;
 use pi_hole_api::{
     AuthenticatedPiHoleAPI, PiHoleAPIConfig, PiHoleAPIConfigWithKey, UnauthenticatedPiHoleAPI,
 };
@@ -296,14 +298,9 @@ fn get_queries_count_test(ctx: &mut PiHoleTestContext) {
 fn add_test(ctx: &mut PiHoleTestContext) {
     let response = ctx
         .authenticated_api
-        .list_add("testdomain.foo", "white")
+        .list_add("testdomain.foo", DomainList::ExactAllow)
         .unwrap();
     assert!(response.success);
-
-    let response = ctx
-        .authenticated_api
-        .list_add("testdomain.foo", "NOT_A_LIST");
-    assert!(matches!(response.err().unwrap(), APIError::InvalidList));
 }
 
 #[test_context(PiHoleTestContext)]
@@ -312,14 +309,9 @@ fn add_test(ctx: &mut PiHoleTestContext) {
 fn remove_test(ctx: &mut PiHoleTestContext) {
     let response = ctx
         .authenticated_api
-        .list_remove("x.testdomain.foo", "white")
+        .list_remove("x.testdomain.foo", DomainList::ExactAllow)
         .unwrap();
     assert!(response.success);
-
-    let response = ctx
-        .authenticated_api
-        .list_remove("x.testdomain.foo", "NOT_A_LIST");
-    assert!(matches!(response.err().unwrap(), APIError::InvalidList));
 }
 
 #[test_context(PiHoleTestContext)]
@@ -327,10 +319,13 @@ fn remove_test(ctx: &mut PiHoleTestContext) {
 #[serial]
 fn list_get_domains_test(ctx: &mut PiHoleTestContext) {
     ctx.authenticated_api
-        .list_add("testdomain.foo", "white")
+        .list_add("testdomain.foo", DomainList::ExactAllow)
         .unwrap();
 
-    let domains = ctx.authenticated_api.list_get_domains("white").unwrap();
+    let domains = ctx
+        .authenticated_api
+        .list_get_domains(DomainList::ExactAllow)
+        .unwrap();
     assert!(domains.len() > 0);
     assert!(domains
         .iter()
@@ -351,10 +346,13 @@ fn list_get_domains_test(ctx: &mut PiHoleTestContext) {
     ));
 
     ctx.authenticated_api
-        .list_remove("testdomain.foo", "white")
+        .list_remove("testdomain.foo", DomainList::ExactAllow)
         .unwrap();
 
-    let domains = ctx.authenticated_api.list_get_domains("white").unwrap();
+    let domains = ctx
+        .authenticated_api
+        .list_get_domains(DomainList::ExactAllow)
+        .unwrap();
     assert_eq!(
         domains
             .iter()
@@ -362,9 +360,6 @@ fn list_get_domains_test(ctx: &mut PiHoleTestContext) {
             .count(),
         0
     );
-
-    let response = ctx.authenticated_api.list_get_domains("NOT_A_LIST");
-    assert!(matches!(response.err().unwrap(), APIError::InvalidList));
 }
 
 #[test_context(PiHoleTestContext)]
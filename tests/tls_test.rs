@@ -0,0 +1,152 @@
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::tls::TlsOptions;
+use pi_hole_api::transport::TransportOptions;
+use pi_hole_api::{PiHoleAPIConfig, UnauthenticatedPiHoleAPI};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+
+const CA_CERT: &[u8] = include_bytes!("data/ca.cert.pem");
+const SERVER_CERT: &[u8] = include_bytes!("data/localhost.cert.pem");
+const SERVER_KEY: &[u8] = include_bytes!("data/localhost.key.pem");
+const SERVER_CERT_FINGERPRINT: &str =
+    "B3:5C:97:17:CD:26:C3:31:07:25:34:5F:E2:2E:F6:02:6F:34:56:B4:3D:ED:28:4A:7E:13:53:BA:83:07:49:D4";
+
+/// Start an HTTPS server on localhost answering every request with a version response.
+/// Returns the base URL of the server.
+fn start_tls_server() -> String {
+    let certificates = CertificateDer::pem_slice_iter(SERVER_CERT)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let key = PrivateKeyDer::from_pem_slice(SERVER_KEY).unwrap();
+    let config = Arc::new(
+        rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(certificates, key)
+        .unwrap(),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let connection = rustls::ServerConnection::new(config.clone()).unwrap();
+            let mut tls_stream = rustls::StreamOwned::new(connection, stream.unwrap());
+            let mut buffer = [0u8; 4096];
+            // Handshake failures are expected when the client rejects the certificate
+            if tls_stream.read(&mut buffer).is_err() {
+                continue;
+            }
+            let body = r#"{"version": 3}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = tls_stream.write_all(response.as_bytes());
+            tls_stream.conn.send_close_notify();
+            let _ = tls_stream.flush();
+        }
+    });
+    format!("https://localhost:{}", port)
+}
+
+fn api_with_tls(host: String, tls: TlsOptions) -> PiHoleAPIConfig {
+    PiHoleAPIConfig::new(host)
+        .with_transport_options(&TransportOptions::new().with_tls(tls))
+        .unwrap()
+}
+
+#[test]
+fn tls_rejects_unknown_certificate_test() {
+    let host = start_tls_server();
+    let response = PiHoleAPIConfig::new(host).get_version();
+    assert!(matches!(
+        response.err().unwrap().kind(),
+        APIErrorKind::RequestError(_)
+    ));
+}
+
+#[test]
+fn tls_custom_root_certificate_test() {
+    let host = start_tls_server();
+    let tls = TlsOptions::new()
+        .add_root_certificates_pem(CA_CERT)
+        .unwrap();
+    assert_eq!(api_with_tls(host, tls).get_version().unwrap(), 3);
+}
+
+#[test]
+fn tls_pinned_certificate_test() {
+    let host = start_tls_server();
+    let tls = TlsOptions::new()
+        .pin_certificate_sha256(SERVER_CERT_FINGERPRINT)
+        .unwrap();
+    assert_eq!(api_with_tls(host, tls).get_version().unwrap(), 3);
+}
+
+#[test]
+fn tls_rejects_mismatched_pin_test() {
+    let host = start_tls_server();
+    let tls = TlsOptions::new()
+        .add_root_certificates_pem(CA_CERT)
+        .unwrap()
+        .pin_certificate_sha256(&"00".repeat(32))
+        .unwrap();
+    let response = api_with_tls(host, tls).get_version();
+    assert!(matches!(
+        response.err().unwrap().kind(),
+        APIErrorKind::RequestError(_)
+    ));
+}
+
+#[test]
+fn tls_accept_invalid_certs_test() {
+    let host = start_tls_server();
+    let tls = TlsOptions::new().danger_accept_invalid_certs(true);
+    assert_eq!(api_with_tls(host, tls).get_version().unwrap(), 3);
+}
+
+#[test]
+fn tls_invalid_options_test() {
+    assert!(matches!(
+        TlsOptions::new()
+            .pin_certificate_sha256("AB:CD")
+            .err()
+            .unwrap()
+            .kind(),
+        APIErrorKind::InvalidTlsConfig(_)
+    ));
+    assert!(matches!(
+        TlsOptions::new()
+            .add_root_certificates_pem(b"not a certificate")
+            .err()
+            .unwrap()
+            .kind(),
+        APIErrorKind::InvalidTlsConfig(_)
+    ));
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn tls_async_pinned_certificate_test() {
+    use pi_hole_api::AsyncUnauthenticatedPiHoleAPI;
+
+    let host = start_tls_server();
+    let tls = TlsOptions::new()
+        .pin_certificate_sha256(SERVER_CERT_FINGERPRINT)
+        .unwrap();
+    let api = api_with_tls(host, tls);
+    assert_eq!(
+        AsyncUnauthenticatedPiHoleAPI::get_version(&api)
+            .await
+            .unwrap(),
+        3
+    );
+}
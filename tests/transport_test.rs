@@ -0,0 +1,150 @@
+mod common;
+
+use common::{key_api, unauthenticated_api};
+use pi_hole_api::api_types::DomainList;
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::transport::{HttpMethod, HttpResponse, MemoryTransport};
+use pi_hole_api::{
+    AuthenticatedPiHoleAPI, PiHoleAPIConfig, PiHoleAPIConfigWithKey, UnauthenticatedPiHoleAPI,
+};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn memory_transport_unauthenticated_request_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"version": 3}"#);
+
+    let version = unauthenticated_api(&transport).get_version().unwrap();
+    assert_eq!(version, 3);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, HttpMethod::Get);
+    assert_eq!(
+        requests[0].url.as_str(),
+        "http://pi.hole/admin/api.php?version"
+    );
+}
+
+#[test]
+fn memory_transport_authenticated_request_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body(r#"{"status": "disabled"}"#);
+
+    let status = key_api(&transport).disable(10).unwrap();
+    assert_eq!(status.status, "disabled");
+
+    let request = &transport.requests()[0];
+    let query: Vec<(String, String)> = request.url.query_pairs().into_owned().collect();
+    assert_eq!(
+        query,
+        vec![
+            ("disable".to_string(), "10".to_string()),
+            ("auth".to_string(), "key".to_string())
+        ]
+    );
+}
+
+#[test]
+fn memory_transport_error_status_test() {
+    let transport = MemoryTransport::new();
+    transport.push_response(HttpResponse::new(500, "Internal Server Error"));
+
+    let response = unauthenticated_api(&transport).get_summary();
+    assert!(matches!(
+        response.err().unwrap().kind(),
+        APIErrorKind::HttpStatus(500)
+    ));
+}
+
+#[test]
+fn memory_transport_error_test() {
+    let transport = MemoryTransport::new();
+    transport.push_error("connection refused");
+
+    let response = unauthenticated_api(&transport).get_summary();
+    assert!(matches!(
+        response.err().unwrap().kind(),
+        APIErrorKind::TransportError(_)
+    ));
+}
+
+#[test]
+fn memory_transport_detects_response_errors_test() {
+    let transport = MemoryTransport::new();
+    transport.push_body("Invalid list [audit]");
+
+    let response = key_api(&transport).list_get_domains(DomainList::Audit);
+    assert!(matches!(
+        response.err().unwrap().kind(),
+        APIErrorKind::InvalidList
+    ));
+}
+
+/// Start an HTTP server on localhost which keeps connections alive and answers every request
+/// with a version response. Returns the base URL and a counter of accepted connections.
+fn start_keep_alive_server() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let connections_counter = connections.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            connections_counter.fetch_add(1, Ordering::SeqCst);
+            let stream = stream.unwrap();
+            thread::spawn(move || {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut writer = stream;
+                loop {
+                    let mut line = String::new();
+                    // Read the request headers up to the blank line
+                    loop {
+                        line.clear();
+                        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                            return;
+                        }
+                        if line == "\r\n" {
+                            break;
+                        }
+                    }
+                    let body = r#"{"version": 3}"#;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    if writer.write_all(response.as_bytes()).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    (format!("http://127.0.0.1:{}", port), connections)
+}
+
+#[test]
+fn config_is_clone_send_sync_test() {
+    fn assert_clone_send_sync<T: Clone + Send + Sync>() {}
+    assert_clone_send_sync::<PiHoleAPIConfig>();
+    assert_clone_send_sync::<PiHoleAPIConfigWithKey>();
+}
+
+#[test]
+fn connections_are_reused_across_clones_and_threads_test() {
+    let (host, connections) = start_keep_alive_server();
+    let api = PiHoleAPIConfig::new(host);
+
+    assert_eq!(api.get_version().unwrap(), 3);
+    let cloned_api = api.clone();
+    thread::spawn(move || assert_eq!(cloned_api.get_version().unwrap(), 3))
+        .join()
+        .unwrap();
+    assert_eq!(api.get_version().unwrap(), 3);
+
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,266 @@
+mod common;
+
+use common::{unauthenticated_api, v6_api};
+use pi_hole_api::api_types::DomainList;
+use pi_hole_api::errors::APIErrorKind;
+use pi_hole_api::ftl_types::{QueryStatus, QueryType, ReplyType};
+use pi_hole_api::transport::{HttpMethod, HttpRequest, HttpResponse, MemoryTransport};
+use pi_hole_api::v6::{PiHoleV6API, PiHoleV6APIConfig};
+use std::net::IpAddr;
+use std::time::Duration;
+
+fn push_login(transport: &MemoryTransport, sid: &str) {
+    transport.push_body(&format!(
+        r#"{{"session": {{"valid": true, "totp": false, "sid": "{}", "csrf": "csrf", "validity": 1800}}, "took": 0.01}}"#,
+        sid
+    ));
+}
+
+fn sid(request: &HttpRequest) -> Option<&str> {
+    request
+        .headers
+        .iter()
+        .find(|(name, _)| name == "X-FTL-SID")
+        .map(|(_, value)| value.as_str())
+}
+
+#[test]
+fn v6_login_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "sid1");
+    transport.push_body(r#"{"blocking": "enabled", "timer": null, "took": 0.01}"#);
+
+    let status = v6_api(&transport).get_status().unwrap();
+    assert_eq!(status.status, "enabled");
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].method, HttpMethod::Post);
+    assert_eq!(requests[0].url.as_str(), "http://pi.hole/api/auth");
+    assert_eq!(
+        requests[0].body.as_deref(),
+        Some(r#"{"password":"password"}"#.as_bytes())
+    );
+    assert_eq!(requests[1].url.as_str(), "http://pi.hole/api/dns/blocking");
+    assert_eq!(sid(&requests[1]), Some("sid1"));
+}
+
+#[test]
+fn v6_base_path_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "sid1");
+    transport.push_body(r#"{"blocking": "enabled", "timer": null, "took": 0.01}"#);
+
+    let config = unauthenticated_api(&transport)
+        .with_base_path("/pihole/admin/")
+        .unwrap();
+    PiHoleV6APIConfig::from_config(config, "password".to_string())
+        .get_status()
+        .unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].url.as_str(), "http://pi.hole/pihole/api/auth");
+    assert_eq!(
+        requests[1].url.as_str(),
+        "http://pi.hole/pihole/api/dns/blocking"
+    );
+}
+
+#[test]
+fn v6_wrong_password_test() {
+    let transport = MemoryTransport::new();
+    transport.push_response(HttpResponse::new(
+        401,
+        r#"{"session": {"valid": false, "sid": null}}"#,
+    ));
+
+    assert!(matches!(
+        v6_api(&transport).login().err().unwrap().kind(),
+        APIErrorKind::LoginFailed
+    ));
+}
+
+#[test]
+fn v6_session_expired_relogin_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "sid1");
+    transport.push_response(HttpResponse::new(
+        401,
+        r#"{"error": {"key": "unauthorized"}}"#,
+    ));
+    push_login(&transport, "sid2");
+    transport.push_body(r#"{"blocking": "disabled", "timer": 30}"#);
+
+    let status = v6_api(&transport).disable(30).unwrap();
+    assert_eq!(status.status, "disabled");
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(sid(&requests[3]), Some("sid2"));
+    assert_eq!(
+        requests[3].body.as_deref(),
+        Some(r#"{"blocking":false,"timer":30}"#.as_bytes())
+    );
+}
+
+#[test]
+fn v6_summary_raw_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "sid1");
+    transport.push_body(
+        r#"{
+            "queries": {"total": 100, "blocked": 25, "percent_blocked": 25.0, "unique_domains": 40,
+                "forwarded": 50, "cached": 25, "frequency": 1.5, "types": {"A": 80, "AAAA": 20},
+                "status": {}, "replies": {"NODATA": 1, "NXDOMAIN": 2, "CNAME": 3, "IP": 4}},
+            "clients": {"active": 5, "total": 7},
+            "gravity": {"domains_being_blocked": 1000, "last_update": 1700000000},
+            "took": 0.01
+        }"#,
+    );
+    transport.push_body(r#"{"blocking": "enabled", "timer": null}"#);
+    transport.push_body(r#"{"config": {"misc": {"privacylevel": 1}}}"#);
+
+    let summary = v6_api(&transport).get_summary_raw().unwrap();
+    assert_eq!(summary.domains_being_blocked, 1000);
+    assert_eq!(summary.dns_queries_today, 100);
+    assert_eq!(summary.ads_blocked_today, 25);
+    assert_eq!(summary.unique_clients, 5);
+    assert_eq!(summary.clients_ever_seen, 7);
+    assert_eq!(summary.reply_ip, 4);
+    assert_eq!(summary.privacy_level, 1);
+    assert_eq!(summary.status, "enabled");
+}
+
+#[test]
+fn v6_all_queries_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "sid1");
+    transport.push_body(
+        r#"{"queries": [{
+            "id": 1, "time": 1700000000.5, "type": "AAAA", "domain": "example.com",
+            "cname": null, "status": "GRAVITY", "client": {"ip": "192.168.0.2", "name": "laptop"},
+            "dnssec": "INSECURE", "reply": {"type": "IP", "time": 0.25}, "list_id": null,
+            "upstream": null, "ede": {"code": -1, "text": null}
+        }], "cursor": 1, "recordsTotal": 1, "recordsFiltered": 1, "draw": 0}"#,
+    );
+
+    let queries = v6_api(&transport).get_all_queries(10).unwrap();
+    assert_eq!(
+        transport.requests()[1].url.as_str(),
+        "http://pi.hole/api/queries?length=10"
+    );
+    assert_eq!(queries.len(), 1);
+    let query = &queries[0];
+    assert_eq!(query.query_type, QueryType::AAAA);
+    assert_eq!(query.domain, "example.com");
+    assert_eq!(query.client, "laptop");
+    assert_eq!(query.status, QueryStatus::QueryGravity);
+    assert_eq!(query.reply_type, ReplyType::ReplyIP);
+    assert_eq!(query.response_time, Duration::from_millis(250));
+    assert_eq!(query.timestring.and_utc().timestamp(), 1700000000);
+}
+
+#[test]
+fn v6_malformed_queries_test() {
+    let query = |time: &str, reply_time: &str| {
+        format!(
+            r#"{{"queries": [{{
+                "id": 1, "time": {}, "type": "A", "domain": "example.com", "cname": null,
+                "status": "FORWARDED", "client": {{"ip": "192.168.0.2", "name": null}},
+                "dnssec": "INSECURE", "reply": {{"type": "IP", "time": {}}}, "list_id": null,
+                "upstream": null, "ede": null
+            }}]}}"#,
+            time, reply_time
+        )
+    };
+    let transport = MemoryTransport::new();
+    push_login(&transport, "sid1");
+    transport.push_body(&query("1e300", "0.25"));
+    transport.push_body(&query("1700000000", "1e300"));
+
+    let api = v6_api(&transport);
+    for _ in 0..2 {
+        assert!(matches!(
+            api.get_all_queries(10).err().unwrap().kind(),
+            APIErrorKind::InvalidResponse(_)
+        ));
+    }
+}
+
+#[test]
+fn v6_list_add_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "sid1");
+    transport.push_body(
+        r#"{"domains": [], "processed": {"success": [], "errors": [{"item": "(\\.|^)example\\.com$", "error": "UNIQUE constraint failed"}]}}"#,
+    );
+
+    let response = v6_api(&transport)
+        .list_add("example.com", DomainList::WildcardDeny)
+        .unwrap();
+    assert!(!response.success);
+    assert!(response.message.unwrap().contains("UNIQUE"));
+
+    let request = &transport.requests()[1];
+    assert_eq!(
+        request.url.as_str(),
+        "http://pi.hole/api/domains/deny/regex"
+    );
+    assert_eq!(
+        request.body.as_deref(),
+        Some(r#"{"domain":"(\\.|^)example\\.com$","enabled":true}"#.as_bytes())
+    );
+
+    assert!(matches!(
+        v6_api(&transport)
+            .list_add("example.com", DomainList::Audit)
+            .err()
+            .unwrap()
+            .kind(),
+        APIErrorKind::Unsupported(_)
+    ));
+}
+
+#[test]
+fn v6_custom_dns_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "sid1");
+    transport.push_body(
+        r#"{"config": {"dns": {"hosts": ["192.168.0.10 nas.lan", "fd00::1 router.lan"]}}}"#,
+    );
+    transport.push_response(HttpResponse::new(201, ""));
+
+    let api = v6_api(&transport);
+    let records = api.get_custom_dns_records().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].domain, "router.lan");
+    assert_eq!(records[1].ip_address, "fd00::1".parse::<IpAddr>().unwrap());
+
+    let ip: IpAddr = "192.168.0.11".parse().unwrap();
+    assert!(
+        api.add_custom_dns_record(&ip, "printer.lan")
+            .unwrap()
+            .success
+    );
+    let request = &transport.requests()[2];
+    assert_eq!(request.method, HttpMethod::Put);
+    assert_eq!(
+        request.url.as_str(),
+        "http://pi.hole/api/config/dns/hosts/192.168.0.11%20printer.lan"
+    );
+}
+
+#[test]
+fn v6_logout_test() {
+    let transport = MemoryTransport::new();
+    push_login(&transport, "sid1");
+    transport.push_response(HttpResponse::new(204, ""));
+
+    let api = v6_api(&transport);
+    api.login().unwrap();
+    api.logout().unwrap();
+
+    let request = &transport.requests()[1];
+    assert_eq!(request.method, HttpMethod::Delete);
+    assert_eq!(request.url.as_str(), "http://pi.hole/api/auth");
+    assert_eq!(sid(request), Some("sid1"));
+}